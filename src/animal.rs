@@ -1,6 +1,6 @@
 use super::*;
+use ::serde::Deserialize;
 
-pub const SPHERE_RADIUS: f32 = 0.25;
 pub const Z: f32 = 0.0;
 pub const SPEED: f32 = 1.0;
 pub const DISTANCE: f32 = 10.0;
@@ -8,14 +8,45 @@ pub const MIN_MOTION_SLEEP_SECONDS: f32 = 10.0;
 pub const MAX_MOTION_SLEEP_SECONDS: f32 = 30.0;
 pub const MIN_DEATH_TIMEOUT_SECONDS: f32 = 60.0;
 pub const MAX_DEATH_TIMEOUT_SECONDS: f32 = 120.0;
+pub const SPECIES_COUNT: u32 = 3;
+pub const RADIUS: f32 = 0.3;
+pub const MASS: f32 = 1.0;
+pub const GATHER_CHANCE: f32 = 0.3;  // of an idle animal heading back to its herd rather than wandering
+pub const GATHER_RADIUS: f32 = 2.0;  // how close to the middle of the herd counts as gathered
+pub const FLOCKING: bool = true;
+pub const FLOCK_RADIUS: f32 = 3.0;  // same-species animals this close flock together
+pub const SEPARATION_RADIUS: f32 = 1.0;  // and this close push apart
+pub const FLOCK_STRENGTH: f32 = 0.5;  // fraction of walking speed flocking can add
+pub const SIGHT_RADIUS: f32 = 8.0;
+pub const FIELD_OF_VIEW: f32 = 2.1;  // radians, about 120 degrees
+pub const AWARENESS_RADIUS: f32 = 1.5;  // noticed even from behind
+pub const PERCEPTION_INTERVAL: f32 = 0.25;  // seconds between an animal's decisions
+pub const FLEE_SPEED: f32 = 2.0;  // how fast the player must close in to scare timid animals
+pub const STILL_SPEED: f32 = 0.1;  // slower than this counts as standing still
+pub const FLEE_DISTANCE: f32 = 8.0;
+pub const FLEE_PACE: f32 = 2.5;  // times walking speed
+pub const APPROACH_DISTANCE: f32 = 1.5;  // curious animals stop this far short
+pub const WIND_NUDGE: f32 = 0.2;  // fraction of the wind an animal drifts with while moving
+pub const MAX_TARGET_ATTEMPTS: u32 = 8;  // directions tried before giving up until next frame
+pub const MIN_SPEED_FACTOR: f32 = 0.25;  // so nothing is ever stuck, e.g. on unloaded ground
+pub const DROWNING: bool = true;
+pub const SINK_SPEED: f32 = 0.5;
+pub const SINK_DEPTH: f32 = 1.0;
 
 pub struct Plugin;
 
 impl ::bevy::prelude::Plugin for Plugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, |world: &mut World| common::RandomSpawnSystem::<Animal>::on_startup(world));
-        app.add_systems(Startup, AssetSystem::on_startup);
+        app.init_resource::<Tuning>();
+        app.add_event::<SpawnEvent>();
+        app.add_event::<ImpactEvent>();
+        app.add_event::<WanderEvent>();
+        app.add_event::<DrownEvent>();
+        app.add_event::<collision::CollisionEvent>();  // also registered by `collision::CollisionPlugin`
         app.add_systems(Update, MotionSystem::on_update);
+        app.add_systems(Update, FlockSystem::on_update.after(MotionSystem::on_update));
+        app.add_systems(Update, PerceptionSystem::on_update.after(FlockSystem::on_update));
+        app.add_systems(Update, DeathSystem::on_update.after(PerceptionSystem::on_update));
         app.add_systems(Update, {
             SpawnEventSystem::on_update.run_if(event_exists!(SpawnEvent))
         });
@@ -25,60 +56,153 @@ impl ::bevy::prelude::Plugin for Plugin {
 #[derive(Component)]
 pub struct Animal;
 
+impl Animal {
+    pub fn bundle(position: Vec2, tuning: &Tuning) -> (Self, Species, Transform, WanderTracker, DeathTracker, FlockTracker, Perception, assets::PropIOU, audio::SoundEmitter, ground::GroundFollower, collision::Collider) {(
+        Self,
+        Species::random(),
+        Transform::from_translation(position.extend(Z)),
+        WanderTracker::new(tuning),
+        DeathTracker::new(tuning),
+        FlockTracker::default(),
+        Perception::default(),
+        assets::PropIOU::Animal,
+        audio::SoundEmitter::default(),
+        ground::GroundFollower::default(),
+        collision::Collider::dynamic(RADIUS, MASS)
+    )}
+}
+
+// Identifies which kind of animal this is, e.g. for its call.
+#[derive(Component)]
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(PartialEq)]
+#[derive(Eq)]
+#[derive(Hash)]
+#[derive(Debug)]
+pub struct Species(pub u32);
+
+impl Species {
+    pub fn random() -> Self {
+        Self(::fastrand::u32(0..SPECIES_COUNT))
+    }
+}
+
 impl common::RandomSpawnEntityConstructor for Animal {
     type Bundle = (
-        Self, 
-        Transform, 
-        MotionTracker,
-        Mesh3d, 
-        MeshMaterial3d<StandardMaterial>
+        Self,
+        Species,
+        Transform,
+        WanderTracker,
+        DeathTracker,
+        FlockTracker,
+        Perception,
+        assets::PropIOU,
+        audio::SoundEmitter,
+        ground::GroundFollower,
+        collision::Collider
     );
 
     fn new(world: &World, position: Vec3) -> Self::Bundle {
-        let asset: &Asset = world.get_resource::<Asset>().expect("`animal::Asset` not initialized.");
-        let mesh: Handle<Mesh> = asset.mesh.to_owned();
-        let material: Handle<StandardMaterial> = asset.material.to_owned();
-        {(
-            Self,
-            position.into(),
-            MotionTracker::default(),
-            Mesh3d(mesh),
-            MeshMaterial3d(material)
-        )}
+        match world.get_resource::<Tuning>() {
+            Some(tuning) => Self::bundle(position.truncate(), tuning),
+            None => Self::bundle(position.truncate(), &Tuning::default())
+        }
     }
 }
 
 
-// === Asset System ===
+// === Tuning ===
+// NOTE: Defaults mirror the constants above and are overwritten live by
+//       `tuning::TuningPlugin`.
 
-// THIS IS TEMPORARY UNTIL PROPS ARE DONE
 #[derive(Resource)]
-pub struct Asset {
-    pub mesh: Handle<Mesh>,
-    pub material: Handle<StandardMaterial>
+#[derive(Clone)]
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct Tuning {
+    pub speed: f32,
+    pub distance: f32,
+    pub min_motion_sleep_seconds: f32,
+    pub max_motion_sleep_seconds: f32,
+    pub min_death_timeout_seconds: f32,
+    pub max_death_timeout_seconds: f32,
+    pub drowning: bool,
+    pub gather_chance: f32,
+    pub steering: navigation::Steering,
+    pub flocking: bool,
+    pub flock_radius: f32,
+    pub flock_weights: Vec<FlockWeights>,  // indexed by species, missing species use the default
+    pub temperaments: Vec<Temperament>  // likewise
 }
 
-pub struct AssetSystem;
+impl Tuning {
+    pub fn flock_weights(&self, species: Species) -> FlockWeights {
+        self.flock_weights.get(species.0 as usize).copied().unwrap_or_default()
+    }
 
-impl AssetSystem {
-    pub fn on_startup(mut commands: Commands, mut meshes: ResMut<::bevy::Asset<Mesh>>, mut materials: ResMut<::bevy::Asset<StandardMaterial>>, mut event_writer: EventWriter<SpawnEvent>) {
-        //             Color::linear_rgb(0.0, 1.0, 0.0),
-        let material: StandardMaterial = StandardMaterial {
-            base_color: (0.0, 1.0, 0.0).into(),
-            unlit: true,
-            ..default()
-        };
-        let material: Handle<StandardMaterial> = materials.add(material);
-        let mesh: Handle<Mesh> = meshes.add(Sphere::new(SPHERE_RADIUS));
-        let asset: Asset = Asset {
-            mesh,
-            material
-        };
-        commands.insert_resource(asset);
-        let event: SpawnEvent = SpawnEvent {
-            position: Vec2::ZERO
-        };
-        event_writer.write(event);
+    pub fn temperament(&self, species: Species) -> Temperament {
+        self.temperaments.get(species.0 as usize).copied().unwrap_or_default()
+    }
+}
+
+// How a species reacts to the player.
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(PartialEq)]
+#[derive(Eq)]
+#[derive(Debug)]
+#[derive(Default)]
+#[derive(Deserialize)]
+pub enum Temperament {
+    Timid,  // flees when the player closes in fast
+    Curious,  // comes closer while the player stands still
+    #[default]
+    Indifferent
+}
+
+// How strongly each flocking rule steers one species.
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct FlockWeights {
+    pub separation: f32,
+    pub alignment: f32,
+    pub cohesion: f32
+}
+
+impl Default for FlockWeights {
+    fn default() -> Self {
+        Self {
+            separation: 1.5,
+            alignment: 1.0,
+            cohesion: 1.0
+        }
+    }
+}
+
+impl Default for Tuning {
+    fn default() -> Self {
+        Self {
+            speed: SPEED,
+            distance: DISTANCE,
+            min_motion_sleep_seconds: MIN_MOTION_SLEEP_SECONDS,
+            max_motion_sleep_seconds: MAX_MOTION_SLEEP_SECONDS,
+            min_death_timeout_seconds: MIN_DEATH_TIMEOUT_SECONDS,
+            max_death_timeout_seconds: MAX_DEATH_TIMEOUT_SECONDS,
+            drowning: DROWNING,
+            gather_chance: GATHER_CHANCE,
+            steering: navigation::Steering::default(),
+            flocking: FLOCKING,
+            flock_radius: FLOCK_RADIUS,
+            flock_weights: vec![
+                FlockWeights {separation: 1.5, alignment: 1.0, cohesion: 1.5},  // tight herds
+                FlockWeights {separation: 1.5, alignment: 1.5, cohesion: 0.8},  // loose, travelling flocks
+                FlockWeights {separation: 2.0, alignment: 0.2, cohesion: 0.2}  // mostly solitary
+            ],
+            temperaments: vec![Temperament::Timid, Temperament::Curious, Temperament::Indifferent]
+        }
     }
 }
 
@@ -95,17 +219,9 @@ pub struct SpawnEvent {
 pub struct SpawnEventSystem;
 
 impl SpawnEventSystem {
-    pub fn on_update(mut commands: Commands, mut event_reader: EventReader<SpawnEvent>, asset: Res<Asset>) {
+    pub fn on_update(mut commands: Commands, mut event_reader: EventReader<SpawnEvent>, tuning: Res<Tuning>) {
         for event in event_reader.read() {
-            let position: Vec3 = event.position.into();
-            let mesh: Handle<Mesh> = asset.mesh.to_owned();
-            let material: Handle<StandardMaterial> = asset.material.to_owned();
-            commands.spawn((
-                Animal,
-                Transform::from_translation(position),
-                Mesh3d(mesh),
-                MeshMaterial3d(material)
-            ));
+            commands.spawn(Animal::bundle(event.position, &tuning));
         }
     }
 }
@@ -113,12 +229,21 @@ impl SpawnEventSystem {
 
 // === Motion System ===
 // This system is responsible for the animals randomly picking
-// a spot, and moving to it.
+// a spot, or their herd, and walking to it around trees, water and
+// rock. Shallow water slows the animal down.
+
+// Written when an animal leaves idle and sets off towards a new spot.
+#[derive(Event)]
+#[derive(Clone)]
+#[derive(Copy)]
+pub struct WanderEvent {
+    pub entity: Entity,
+    pub position: Vec2
+}
 
 pub struct InMotionLifecycle {
-    pub from: Vec2,
-    pub to: Vec2,
-    pub progress: f32
+    pub route: navigation::Route,
+    pub pace: f32  // times walking speed
 }
 
 #[derive(Default)]
@@ -129,44 +254,82 @@ pub enum MotionLifecycle {
     Shutdown,
 }
 
+// Wander lifecycle of an animal. Per-frame velocity for animating the
+// animal's prop is tracked separately by `assets::MotionTracker`.
 #[derive(Component)]
-#[derive(Default)]
-pub struct MotionTracker {
+pub struct WanderTracker {
     pub lifecycle: MotionLifecycle,
     pub cooldown: f32
 }
 
-impl MotionTracker {
-    pub fn random_cooldown() -> f32 {
-        MIN_MOTION_SLEEP_SECONDS + fastrand::f32() * (MAX_MOTION_SLEEP_SECONDS - MIN_MOTION_SLEEP_SECONDS)
+impl WanderTracker {
+    pub fn new(tuning: &Tuning) -> Self {
+        Self {
+            lifecycle: MotionLifecycle::default(),
+            cooldown: Self::random_cooldown(tuning)
+        }
+    }
+
+    pub fn random_cooldown(tuning: &Tuning) -> f32 {
+        tuning.min_motion_sleep_seconds + fastrand::f32() * (tuning.max_motion_sleep_seconds - tuning.min_motion_sleep_seconds)
+    }
+}
+
+// Plans a path on the nav grid when there is one, or heads straight there.
+fn plan_path(nav_grid: Option<&mut navigation::NavGrid>, ground: Option<&ground::GroundMap>, from: Vec2, to: Vec2) -> Option<navigation::Path> {
+    match (nav_grid, ground) {
+        (Some(nav_grid), Some(ground)) => nav_grid.find_path(ground, from, to),
+        _ => Some(navigation::Path::straight(to))
     }
 }
 
-impl Default for MotionTracker {
-    fn default() -> Self {
-        Self {
-            lifecycle: MotionLifecycle::default(),
-            cooldown: Self::random_cooldown()
+// Follows the shared field for `goal`, if it leads anywhere from where the
+// animal is.
+fn flow_route(flow_fields: Option<&navigation::FlowFields>, goal: navigation::FlowGoal, until: f32, position: Vec2) -> Option<navigation::Route> {
+    flow_fields?.get(goal)?.direction(position, goal.ascends())?;
+    Some(navigation::Route::Flow(navigation::FlowRoute::new(goal, until)))
+}
+
+// Heads for the middle of the animal's herd, by whichever method is tuned.
+fn gather_route(nav_grid: Option<&mut navigation::NavGrid>, ground: Option<&ground::GroundMap>, flow_fields: Option<&navigation::FlowFields>, tuning: &Tuning, species: Species, position: Vec2) -> Option<navigation::Route> {
+    let goal: navigation::FlowGoal = navigation::FlowGoal::Gather(species);
+    match tuning.steering.method(goal) {
+        navigation::Method::FlowField => flow_route(flow_fields, goal, GATHER_RADIUS, position),
+        navigation::Method::AStar => {
+            let center: Vec2 = *flow_fields?.sources(goal).first()?;
+            if center.distance(position) < GATHER_RADIUS {
+                return None
+            }
+            plan_path(nav_grid, ground, position, center).map(navigation::Route::Path)
         }
     }
 }
 
-pub struct MotionSystem {
+// Everything an animal finds its way with, shared by the systems that
+// move animals along routes.
+#[derive(::bevy::ecs::system::SystemParam)]
+pub struct Wayfinding<'w> {
+    ground: Option<Res<'w, ground::GroundMap>>,
+    nav_grid: Option<ResMut<'w, navigation::NavGrid>>,
+    flow_fields: Option<Res<'w, navigation::FlowFields>>
+}
+
+pub struct MotionSystem<'a> {
     x: f32,
     y: f32,
-    world_w: f32,
-    world_h: f32,
-    distance: f32
+    distance: f32,
+    ground: Option<&'a ground::GroundMap>
 }
 
-impl MotionSystem {
-    pub fn on_update(mut animals: Query<(&mut Transform, &mut MotionTracker, &mut Animal)>, time: Res<Time>) {
-        let world_w: f32 = WORLD_W;
-        let world_h: f32 = WORLD_H;
+impl MotionSystem<'_> {
+    pub fn on_update(mut animals: Query<(Entity, &mut Transform, &mut WanderTracker, &Species, &mut Animal)>, time: Res<Time>, tuning: Res<Tuning>, time_of_day: Option<Res<environment::TimeOfDay>>, wind: Option<Res<environment::Wind>>, wayfinding: Wayfinding, mut wander_writer: EventWriter<WanderEvent>) {
+        let Wayfinding {ground, mut nav_grid, flow_fields} = wayfinding;
+        let sleeping: bool = time_of_day.is_some_and(|t| t.phase() == environment::DayPhase::Night);
         let delta: f32 = time.delta_secs();
-        let speed: f32 = SPEED;
-        let distance: f32 = DISTANCE;
-        for (mut transform, mut tracker, _) in animals.iter_mut() {
+        let speed: f32 = tuning.speed;
+        let distance: f32 = tuning.distance;
+        let drift: Vec2 = wind.map_or(Vec2::ZERO, |wind| wind.vector * WIND_NUDGE * delta);
+        for (entity, mut transform, mut tracker, &species, _) in animals.iter_mut() {
             let position: Vec2 = transform.translation.truncate();
             match &mut tracker.lifecycle {
                 MotionLifecycle::Shutdown => {},
@@ -175,31 +338,61 @@ impl MotionSystem {
                         tracker.cooldown = (tracker.cooldown - delta).max(0.0);
                         continue
                     }
-                    let x: f32 = position.x;
-                    let y: f32 = position.y;
-                    let model: Self = Self {
-                        x,
-                        y,
-                        world_w,
-                        world_h,
-                        distance
+                    if sleeping {
+                        continue
+                    }
+                    let gathering: Option<navigation::Route> = if ::fastrand::f32() < tuning.gather_chance {
+                        gather_route(nav_grid.as_deref_mut(), ground.as_deref(), flow_fields.as_deref(), &tuning, species, position)
+                    } else {
+                        None
+                    };
+                    let route: navigation::Route = match gathering {
+                        Some(route) => route,
+                        None => {
+                            let x: f32 = position.x;
+                            let y: f32 = position.y;
+                            let model: MotionSystem = MotionSystem {
+                                x,
+                                y,
+                                distance,
+                                ground: ground.as_deref()
+                            };
+                            let Some((x, y)) = model.simulate() else {
+                                continue  // try again next frame
+                            };
+                            let Some(path) = plan_path(nav_grid.as_deref_mut(), ground.as_deref(), position, (x, y).into()) else {
+                                continue  // unreachable, try again next frame
+                            };
+                            navigation::Route::Path(path)
+                        }
                     };
-                    let (x, y) = model.simulate();
                     let lifecycle: InMotionLifecycle = InMotionLifecycle {
-                        from: position,
-                        to: (x, y).into(),
-                        progress: 0.0
+                        route,
+                        pace: 1.0
                     };
                     tracker.lifecycle = MotionLifecycle::InMotion(lifecycle);
+                    let event: WanderEvent = WanderEvent {
+                        entity,
+                        position
+                    };
+                    wander_writer.write(event);
                 },
                 MotionLifecycle::InMotion(lifecycle) => {
-                    lifecycle.progress += delta * speed;
-                    let t: f32 = lifecycle.progress.clamp(0.0, 1.0);
-                    let t_eased: f32 = (t * ::std::f32::consts::PI).sin();
-                    transform.translation = lifecycle.from.lerp(*lifecycle.to, t_eased).into();
-                    if t >= 1.0 {
+                    // Steers from wherever the animal is rather than snapping
+                    // onto the path, so collisions can push it off the path.
+                    let speed_factor: f32 = ground.as_ref().map_or(1.0, |ground| ground.speed_factor(position).max(MIN_SPEED_FACTOR));
+                    let Some(mut position) = lifecycle.route.advance(nav_grid.as_deref_mut(), ground.as_deref(), flow_fields.as_deref(), position, delta * speed * lifecycle.pace * speed_factor) else {
+                        tracker.lifecycle = MotionLifecycle::Idle;  // the way closed up behind it
+                        tracker.cooldown = WanderTracker::random_cooldown(&tuning);
+                        continue
+                    };
+                    if ground.as_ref().is_none_or(|ground| ground.is_walkable(position + drift)) {
+                        position += drift;
+                    }
+                    transform.translation = position.extend(transform.translation.z);
+                    if lifecycle.route.is_finished() {
                         tracker.lifecycle = MotionLifecycle::Idle;
-                        tracker.cooldown = MotionTracker::random_cooldown();
+                        tracker.cooldown = WanderTracker::random_cooldown(&tuning);
                     }
                 }
             }
@@ -207,27 +400,300 @@ impl MotionSystem {
     }
 }
 
-impl Model for MotionSystem {
-    type Output = (f32, f32);
+impl Model for MotionSystem<'_> {
+    type Output = Option<(f32, f32)>;
     
+    // Picks a random spot on walkable ground. Whether it can be reached is
+    // up to the path finder.
+    fn simulate(self) -> Self::Output {
+        for _ in 0..MAX_TARGET_ATTEMPTS {
+            let angle: f32 = ::fastrand::f32() * ::std::f32::consts::TAU;
+            let offset_x: f32 = angle.cos() * self.distance;
+            let offset_y: f32 = angle.sin() * self.distance;
+            let new_x: f32 = self.x + offset_x;
+            let new_y: f32 = self.y + offset_y;
+            let Some(ground) = self.ground else {
+                return Some((new_x, new_y))
+            };
+            if ground.is_walkable((new_x, new_y).into()) {
+                return Some((new_x, new_y))
+            }
+        }
+        None
+    }
+}
+
+
+// === Flock System ===
+// NOTE: Layered over the wander cycle: animals on the move separate from,
+//       align with and close in on nearby animals of their own species,
+//       while idle ones only keep their distance.
+
+#[derive(Component)]
+#[derive(Default)]
+pub struct FlockTracker {
+    pub velocity: Vec2,
+    last_position: Option<Vec2>
+}
+
+pub struct FlockSystem {
+    position: Vec2,
+    velocity: Vec2,
+    neighbours: Vec<(Vec2, Vec2)>,
+    weights: FlockWeights,
+    radius: f32
+}
+
+type FlockQuery<'w, 's> = Query<'w, 's, (Entity, &'static mut Transform, &'static Species, &'static WanderTracker, &'static DeathTracker, &'static mut FlockTracker), With<Animal>>;
+
+impl FlockSystem {
+    pub fn on_update(mut animals: FlockQuery, time: Res<Time>, tuning: Res<Tuning>, ground: Option<Res<ground::GroundMap>>) {
+        let delta: f32 = time.delta_secs();
+        if delta <= 0.0 {
+            return
+        }
+        let mut index: common::SpatialIndex<(Entity, Species, Vec2)> = common::SpatialIndex::new(tuning.flock_radius.max(f32::EPSILON));
+        for (entity, transform, &species, _, death_tracker, mut flock_tracker) in animals.iter_mut() {
+            let position: Vec2 = transform.translation.truncate();
+            flock_tracker.velocity = flock_tracker.last_position.map_or(Vec2::ZERO, |last| (position - last) / delta);
+            flock_tracker.last_position = Some(position);
+            if matches!(death_tracker.lifecycle, DeathLifecycle::Alive) {
+                index.insert(position, (entity, species, flock_tracker.velocity));
+            }
+        }
+        if !tuning.flocking {
+            return
+        }
+        for (entity, mut transform, &species, wander_tracker, death_tracker, mut flock_tracker) in animals.iter_mut() {
+            if !matches!(death_tracker.lifecycle, DeathLifecycle::Alive) {
+                continue
+            }
+            let mut weights: FlockWeights = tuning.flock_weights(species);
+            match wander_tracker.lifecycle {
+                MotionLifecycle::InMotion(_) => {},
+                MotionLifecycle::Idle => {
+                    weights.alignment = 0.0;
+                    weights.cohesion = 0.0;
+                },
+                MotionLifecycle::Shutdown => continue
+            }
+            let position: Vec2 = transform.translation.truncate();
+            let neighbours: Vec<(Vec2, Vec2)> = index
+                .query(position, tuning.flock_radius)
+                .filter(|(_, (other, other_species, _))| *other != entity && *other_species == species)
+                .map(|(other_position, &(_, _, other_velocity))| (other_position, other_velocity))
+                .collect();
+            if neighbours.is_empty() {
+                continue
+            }
+            let model: Self = Self {
+                position,
+                velocity: flock_tracker.velocity,
+                neighbours,
+                weights,
+                radius: tuning.flock_radius
+            };
+            let offset: Vec2 = model.simulate() * tuning.speed * FLOCK_STRENGTH * delta;
+            if ground.as_ref().is_some_and(|ground| !ground.is_walkable(position + offset)) {
+                continue
+            }
+            transform.translation += offset.extend(0.0);
+            flock_tracker.last_position = Some(position + offset);  // flocking isn't counted as the animal's own velocity
+        }
+    }
+}
+
+impl Model for FlockSystem {
+    type Output = Vec2;
+
+    // Weighted sum of the three flocking rules, no longer than 1.0.
+    fn simulate(self) -> Self::Output {
+        let count: f32 = self.neighbours.len() as f32;
+        let separation: Vec2 = self.neighbours
+            .iter()
+            .map(|&(other, _)| self.position - other)
+            .filter(|offset| offset.length() < SEPARATION_RADIUS)
+            .map(|offset| offset.normalize_or_zero() * (1.0 - offset.length() / SEPARATION_RADIUS))
+            .sum();
+        let average_velocity: Vec2 = self.neighbours.iter().map(|&(_, velocity)| velocity).sum::<Vec2>() / count;
+        let alignment: Vec2 = (average_velocity - self.velocity).clamp_length_max(1.0);
+        let center: Vec2 = self.neighbours.iter().map(|&(other, _)| other).sum::<Vec2>() / count;
+        let cohesion: Vec2 = ((center - self.position) / self.radius).clamp_length_max(1.0);
+        let steer: Vec2 = separation * self.weights.separation
+            + alignment * self.weights.alignment
+            + cohesion * self.weights.cohesion;
+        steer.clamp_length_max(1.0)
+    }
+}
+
+
+// === Perception System ===
+// NOTE: Animals watch for the player and the mouse friend within their
+//       sight cone. Timid species flee a player closing in fast, curious
+//       ones come over while the player stands still, and all but timid
+//       ones wander over to the mouse friend, which makes it a lure.
+
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(PartialEq)]
+#[derive(Debug)]
+pub enum Reaction {
+    Flee(Vec2),  // away from here
+    Approach(Vec2)  // towards here
+}
+
+#[derive(Component)]
+pub struct Perception {
+    pub sight_radius: f32,
+    pub field_of_view: f32,
+    pub facing: Vec2,
+    pub reaction: Option<Reaction>,
+    cooldown: f32
+}
+
+impl Perception {
+    pub fn sees(&self, position: Vec2, target: Vec2) -> bool {
+        let offset: Vec2 = target - position;
+        let distance: f32 = offset.length();
+        if distance <= AWARENESS_RADIUS {
+            return true
+        }
+        distance <= self.sight_radius && self.facing.angle_to(offset).abs() <= self.field_of_view * 0.5
+    }
+}
+
+impl Default for Perception {
+    fn default() -> Self {
+        Self {
+            sight_radius: SIGHT_RADIUS,
+            field_of_view: FIELD_OF_VIEW,
+            facing: Vec2::from_angle(::fastrand::f32() * ::std::f32::consts::TAU),
+            reaction: None,
+            cooldown: ::fastrand::f32() * PERCEPTION_INTERVAL  // spread decisions across frames
+        }
+    }
+}
+
+pub struct PerceptionSystem {
+    position: Vec2,
+    temperament: Temperament,
+    player: Option<(Vec2, Vec2)>,  // position and velocity, when seen
+    lure: Option<Vec2>  // when seen
+}
+
+type PerceptionQuery<'w, 's> = Query<'w, 's, (&'static Transform, &'static Species, &'static mut WanderTracker, &'static DeathTracker, &'static FlockTracker, &'static mut Perception), With<Animal>>;
+
+impl PerceptionSystem {
+    pub fn on_update(mut animals: PerceptionQuery, players: Query<&Transform, (With<player_controls::PlayerMarker>, Without<Animal>)>, lures: Query<&Transform, (With<player_controls::FollowsMouse>, Without<Animal>)>, player_motion: Option<Res<player_controls::PlayerMotion>>, time: Res<Time>, tuning: Res<Tuning>, wayfinding: Wayfinding) {
+        let Wayfinding {ground, mut nav_grid, flow_fields} = wayfinding;
+        let delta: f32 = time.delta_secs();
+        let player: Option<(Vec2, Vec2)> = players
+            .single()
+            .ok()
+            .map(|transform| (transform.translation.truncate(), player_motion.as_ref().map_or(Vec2::ZERO, |motion| motion.velocity)));
+        let lure: Option<Vec2> = lures.single().ok().map(|transform| transform.translation.truncate());
+        for (transform, &species, mut wander_tracker, death_tracker, flock_tracker, mut perception) in animals.iter_mut() {
+            let walking: bool = matches!(wander_tracker.lifecycle, MotionLifecycle::InMotion(_));
+            if walking && flock_tracker.velocity.length() > STILL_SPEED {
+                perception.facing = flock_tracker.velocity.normalize();  // idle animals keep looking the same way
+            }
+            if perception.reaction.is_some() && matches!(wander_tracker.lifecycle, MotionLifecycle::Idle) {
+                perception.reaction = None;  // got where it was going
+            }
+            perception.cooldown -= delta;
+            if perception.cooldown > 0.0 {
+                continue
+            }
+            perception.cooldown = PERCEPTION_INTERVAL;
+            if !matches!(death_tracker.lifecycle, DeathLifecycle::Alive) || matches!(wander_tracker.lifecycle, MotionLifecycle::Shutdown) {
+                continue
+            }
+            let position: Vec2 = transform.translation.truncate();
+            let model: Self = Self {
+                position,
+                temperament: tuning.temperament(species),
+                player: player.filter(|&(player_position, _)| perception.sees(position, player_position)),
+                lure: lure.filter(|&lure_position| perception.sees(position, lure_position))
+            };
+            let Some(reaction) = model.simulate() else {
+                continue
+            };
+            let unchanged: bool = match (perception.reaction, reaction) {
+                (Some(Reaction::Flee(_)), Reaction::Flee(_)) => true,  // already running, the flee field follows the player by itself
+                (Some(Reaction::Approach(current)), Reaction::Approach(target)) => current.distance(target) <= ground::TILE_SIZE,  // still heading there
+                _ => false
+            };
+            if unchanged {
+                continue
+            }
+            let route: Option<navigation::Route> = match reaction {
+                Reaction::Flee(from) => {
+                    let goal: navigation::FlowGoal = navigation::FlowGoal::Flee;
+                    let away: Vec2 = position + (position - from).normalize_or(perception.facing) * FLEE_DISTANCE;
+                    match tuning.steering.method(goal) {
+                        navigation::Method::FlowField => flow_route(flow_fields.as_deref(), goal, FLEE_DISTANCE, position),
+                        navigation::Method::AStar => None
+                    }.or_else(|| plan_path(nav_grid.as_deref_mut(), ground.as_deref(), position, away).map(navigation::Route::Path))
+                },
+                Reaction::Approach(target) => {
+                    let stop: Vec2 = target + (position - target).normalize_or_zero() * APPROACH_DISTANCE;
+                    plan_path(nav_grid.as_deref_mut(), ground.as_deref(), position, stop).map(navigation::Route::Path)
+                }
+            };
+            let Some(route) = route else {
+                continue
+            };
+            let pace: f32 = match reaction {
+                Reaction::Flee(_) => FLEE_PACE,
+                Reaction::Approach(_) => 1.0
+            };
+            let lifecycle: InMotionLifecycle = InMotionLifecycle {
+                route,
+                pace
+            };
+            wander_tracker.lifecycle = MotionLifecycle::InMotion(lifecycle);
+            perception.reaction = Some(reaction);
+        }
+    }
+}
+
+impl Model for PerceptionSystem {
+    type Output = Option<Reaction>;
+
+    // Decides how to react to what the animal can see. Fleeing comes first,
+    // then the player, then the lure.
     fn simulate(self) -> Self::Output {
-        let angle: f32 = ::fastrand::f32() * ::std::f32::consts::TAU;
-        let offset_x: f32 = angle.cos() * self.distance;
-        let offset_y: f32 = angle.sin() * self.distance;
-        let new_x: f32 = (self.x + offset_x).clamp(0.0, self.world_w);
-        let new_y: f32 = (self.y + offset_y).clamp(0.0, self.world_h);
-        (new_x, new_y)
+        if let Some((player, velocity)) = self.player {
+            let towards_animal: Vec2 = (self.position - player).normalize_or_zero();
+            let closing_speed: f32 = velocity.dot(towards_animal);
+            match self.temperament {
+                Temperament::Timid if closing_speed >= FLEE_SPEED => return Some(Reaction::Flee(player)),
+                Temperament::Curious if velocity.length() < STILL_SPEED && self.position.distance(player) > APPROACH_DISTANCE * 2.0 => return Some(Reaction::Approach(player)),
+                _ => {}
+            }
+        }
+        self.lure
+            .filter(|_| matches!(self.temperament, Temperament::Curious | Temperament::Indifferent))  // timid ones keep their distance
+            .filter(|lure| self.position.distance(*lure) > APPROACH_DISTANCE * 2.0)
+            .map(Reaction::Approach)
     }
 }
 
 
 // === Death System ===
-// NOTE After timeout the animal will stop motion and ram directly into the
-//      nearest tree.
+// NOTE After timeout the animal will stop motion and make for the nearest
+//      tree, impacting whichever standing tree it collides with. With no
+//      tree standing it keeps wandering until one grows. One that
+//      can't find a way charges straight at it instead, and drowns if that
+//      takes it into deep water while `Tuning::drowning` is on. Either
+//      way its body then fades away.
 
 pub struct DeathRammingLifecycle {
-    pub from: Vec2,
-    pub to: Vec2
+    pub route: navigation::Route
+}
+
+pub struct DeathDrowningLifecycle {
+    pub depth: f32
 }
 
 #[derive(Default)]
@@ -235,6 +701,7 @@ pub enum DeathLifecycle {
     #[default]
     Alive,
     Ramming(DeathRammingLifecycle),
+    Drowning(DeathDrowningLifecycle),
     Corpse
 }
 
@@ -245,18 +712,43 @@ pub struct DeathTracker {
 }
 
 impl DeathTracker {
-    pub fn random_timeout() -> f32 {
-        MIN_DEATH_TIMEOUT_SECONDS + ::fastrand::f32() * (MAX_DEATH_TIMEOUT_SECONDS - MIN_DEATH_TIMEOUT_SECONDS)
-    }
-}
-
-impl Default for DeathTracker {
-    fn default() -> Self {
+    pub fn new(tuning: &Tuning) -> Self {
         Self {
             lifecycle: DeathLifecycle::default(),
-            timeout: Self::random_timeout()
+            timeout: Self::random_timeout(tuning)
         }
     }
+
+    pub fn random_timeout(tuning: &Tuning) -> f32 {
+        tuning.min_death_timeout_seconds + ::fastrand::f32() * (tuning.max_death_timeout_seconds - tuning.min_death_timeout_seconds)
+    }
+}
+
+// Written when a ramming animal runs into its tree.
+#[derive(Event)]
+#[derive(Clone)]
+#[derive(Copy)]
+pub struct ImpactEvent {
+    pub entity: Entity,
+    pub tree: Entity,
+    pub position: Vec2
+}
+
+// Written when a ramming animal walks into deep water.
+#[derive(Event)]
+#[derive(Clone)]
+#[derive(Copy)]
+pub struct DrownEvent {
+    pub entity: Entity,
+    pub position: Vec2
+}
+
+// Collisions read, and impacts and drownings written, by the death system.
+#[derive(::bevy::ecs::system::SystemParam)]
+pub struct DeathEvents<'w, 's> {
+    collision_reader: EventReader<'w, 's, collision::CollisionEvent>,
+    impact_writer: EventWriter<'w, ImpactEvent>,
+    drown_writer: EventWriter<'w, DrownEvent>
 }
 
 pub struct DeathSystem {
@@ -265,65 +757,142 @@ pub struct DeathSystem {
     tree_positions: Vec<(f32, f32)>
 }
 
+type DeathQuery<'w, 's> = Query<'w, 's, (Entity, &'static mut Transform, &'static mut DeathTracker, &'static mut WanderTracker, Option<&'static mut ground::GroundFollower>, Option<&'static mut assets::MotionTracker>, &'static mut Animal)>;
+type TreeQuery<'w, 's> = Query<'w, 's, (Entity, &'static Transform, &'static tree::HealthTracker), (With<tree::Tree>, Without<Animal>)>;
+
 impl DeathSystem {
-    pub fn on_update(mut animals: Query<(&mut Transform, &mut DeathTracker, &mut Animal)>, mut trees: Query<(&mut Transform, &mut tree::Tree)>, time: Res<Time>) {
+    pub fn on_update(mut commands: Commands, mut animals: DeathQuery, trees: TreeQuery, time: Res<Time>, tuning: Res<Tuning>, wayfinding: Wayfinding, events: DeathEvents) {
+        let Wayfinding {ground, mut nav_grid, flow_fields} = wayfinding;
+        let DeathEvents {mut collision_reader, mut impact_writer, mut drown_writer} = events;
         let delta: f32 = time.delta_secs();
         let tree_positions: Vec<(f32, f32)> = trees
             .iter()
-            .map(|(transform, _)| {
+            .filter(|(_, _, health_tracker)| health_tracker.is_standing())
+            .map(|(_, transform, _)| {
                 let position: Vec2 = transform.translation.truncate();
                 let x: f32 = position.x;
                 let y: f32 = position.y;
                 (x, y)
             })
             .collect();
-        for (mut transform, mut tracker, _) in animals.iter_mut() {
+        let is_standing_tree = |entity: Entity| trees.get(entity).is_ok_and(|(_, _, health_tracker)| health_tracker.is_standing());
+        let hits: ::std::collections::HashMap<Entity, Entity> = collision_reader
+            .read()
+            .flat_map(|event| [(event.a, event.b), (event.b, event.a)])
+            .filter(|&(_, other)| is_standing_tree(other))
+            .collect();
+        for (entity, mut transform, mut tracker, mut wander_tracker, ground_follower, motion_tracker, _) in animals.iter_mut() {
             let position: Vec2 = transform.translation.truncate();
             match &mut tracker.lifecycle {
                 DeathLifecycle::Corpse => {},
                 DeathLifecycle::Alive => {
                     tracker.timeout -= delta;
                     if tracker.timeout > 0.0 {
-                        tracker.lifecycle = DeathLifecycle::Corpse;
                         continue
                     }
-                    let x: f32 = position.x;
-                    let y: f32 = position.y;
-                    let model: Self = Self {
-                        x,
-                        y,
-                        tree_positions: tree_positions.to_owned()
+                    let goal: navigation::FlowGoal = navigation::FlowGoal::NearestTree;
+                    let flow: Option<navigation::Route> = match tuning.steering.method(goal) {
+                        navigation::Method::FlowField => flow_route(flow_fields.as_deref(), goal, 0.0, position),
+                        navigation::Method::AStar => None
+                    };
+                    let Some(route) = flow.or_else(|| Self::ram_route(nav_grid.as_deref_mut(), ground.as_deref(), &tree_positions, position)) else {
+                        continue  // no tree to ram, keeps wandering until one grows
                     };
-                    let target: Vec2 = model.simulate();
+                    wander_tracker.lifecycle = MotionLifecycle::Shutdown;
                     let lifecycle: DeathRammingLifecycle = DeathRammingLifecycle {
-                        from: position,
-                        to: target
+                        route
                     };
                     tracker.lifecycle = DeathLifecycle::Ramming(lifecycle);
                 },
                 DeathLifecycle::Ramming(ramming_lifecycle) => {
-                    let direction: Vec2 = (ramming_lifecycle.to - position).normalize_or_zero();
-                    let motion_delta: Vec2 = direction * SPEED * delta;
-                    let position: Vec2 = position + motion_delta;
-                    transform.translation = position.into();
-                    if position.distance(ramming_lifecycle.to) < 0.5 {
+                    let tile: Option<ground::Tile> = ground.as_ref().and_then(|ground| ground.tile_at(position));
+                    if tuning.drowning && tile == Some(ground::Tile::Water) {
+                        let lifecycle: DeathDrowningLifecycle = DeathDrowningLifecycle {
+                            depth: 0.0
+                        };
+                        tracker.lifecycle = DeathLifecycle::Drowning(lifecycle);
+                        commands.entity(entity).remove::<collision::Collider>();  // sinks out of everyone's way
+                        let event: DrownEvent = DrownEvent {
+                            entity,
+                            position
+                        };
+                        drown_writer.write(event);
+                        continue
+                    }
+                    let speed_factor: f32 = tile.map_or(1.0, |tile| tile.speed_factor().max(MIN_SPEED_FACTOR));
+                    let distance: f32 = tuning.speed * speed_factor * delta;
+                    // A route that ran out without a hit, or lost its way, is
+                    // replaced by a charge at whichever tree now stands nearest.
+                    let advanced: Option<Vec2> = if ramming_lifecycle.route.is_finished() {
+                        None
+                    } else {
+                        ramming_lifecycle.route.advance(nav_grid.as_deref_mut(), ground.as_deref(), flow_fields.as_deref(), position, distance)
+                    };
+                    let advanced: Option<Vec2> = advanced.or_else(|| {
+                        ramming_lifecycle.route = Self::ram_route(nav_grid.as_deref_mut(), ground.as_deref(), &tree_positions, position)?;
+                        ramming_lifecycle.route.advance(nav_grid.as_deref_mut(), ground.as_deref(), flow_fields.as_deref(), position, distance)
+                    });
+                    let Some(position) = advanced else {
+                        tracker.lifecycle = DeathLifecycle::Alive;  // no tree left standing, settles back down
+                        tracker.timeout = DeathTracker::random_timeout(&tuning);
+                        wander_tracker.lifecycle = MotionLifecycle::Idle;
+                        continue
+                    };
+                    transform.translation = position.extend(Z);
+                    let Some(&tree) = hits.get(&entity) else {
+                        continue
+                    };
+                    let event: ImpactEvent = ImpactEvent {
+                        entity,
+                        tree,
+                        position
+                    };
+                    impact_writer.write(event);
+                    tracker.lifecycle = DeathLifecycle::Corpse;
+                    commands.entity(entity).remove::<collision::Collider>();
+                    if let Some(mut motion_tracker) = motion_tracker {
+                        motion_tracker.kill();  // fades the body away
+                    }
+                },
+                DeathLifecycle::Drowning(drowning_lifecycle) => {
+                    drowning_lifecycle.depth += SINK_SPEED * delta;
+                    if let Some(mut ground_follower) = ground_follower {
+                        ground_follower.offset = -drowning_lifecycle.depth;
+                    }
+                    if drowning_lifecycle.depth >= SINK_DEPTH {
                         tracker.lifecycle = DeathLifecycle::Corpse;
+                        if let Some(mut motion_tracker) = motion_tracker {
+                            motion_tracker.kill();
+                        }
                     }
                 }
             }
         }
     }
+
+    // Charges the nearest standing tree, around obstacles when there's a
+    // way and straight at it when there isn't.
+    fn ram_route(nav_grid: Option<&mut navigation::NavGrid>, ground: Option<&ground::GroundMap>, tree_positions: &[(f32, f32)], position: Vec2) -> Option<navigation::Route> {
+        let model: Self = Self {
+            x: position.x,
+            y: position.y,
+            tree_positions: tree_positions.to_owned()
+        };
+        let target: Vec2 = model.simulate()?;
+        let path: navigation::Path = plan_path(nav_grid, ground, position, target).unwrap_or(navigation::Path::straight(target));
+        Some(navigation::Route::Path(path))
+    }
 }
 
 impl Model for DeathSystem {
-    type Output = Vec2;
+    type Output = Option<Vec2>;
 
-    // Determines the closest tree to ram into.
+    // Determines the closest tree to ram into, if there are any.
     fn simulate(self) -> Self::Output {
         let position: Vec2 = (self.x, self.y).into();
         let tree_positions: Vec<Vec2> = self.tree_positions
             .iter()
-            .map(|(x, y)| {
+            .map(|&(x, y)| {
                 (x, y).into()
             })
             .collect();
@@ -334,6 +903,5 @@ impl Model for DeathSystem {
                 let dy: f32 = y.distance_squared(position);
                 dx.partial_cmp(&dy).unwrap_or(::std::cmp::Ordering::Equal)
             })
-            .unwrap_or(position)
     }
 }
\ No newline at end of file
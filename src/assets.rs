@@ -1,7 +1,16 @@
+use std::collections::HashMap;
 use bevy::prelude::*;
 use crate::event_exists;
 
+const DEATH_FADE_SECONDS: f32 = 0.5;  // how long a killed prop takes to shrink away
+const POOL_CAPACITY: usize = 64;  // parked standalone props kept per `PropIOU`
+
+mod animal;
 mod hoppy_cube;
+mod tree;
+
+pub use hoppy_cube::HopTuning;
+pub use tree::TreeCrown;
 
 #[derive(Event, Copy, Clone)]  // for spawning props directly
 pub struct PropSpawn {
@@ -10,9 +19,30 @@ pub struct PropSpawn {
     pub transform: Option<Transform>
 }
 
+#[derive(Event, Copy, Clone)]  // for removing props, mirrors `PropSpawn`
+pub struct PropDespawn {
+    pub entity: Entity,
+    pub fade: Option<f32>  // seconds spent shrinking before removal, `None` removes immediately
+}
+
+#[derive(Event, Copy, Clone)]  // written when a hopping prop leaves the ground
+pub struct HopStarted {
+    pub entity: Entity,
+    pub position: Vec2
+}
+
+#[derive(Event, Copy, Clone)]  // written when a hopping prop touches the ground again
+pub struct HopLanded {
+    pub entity: Entity,
+    pub position: Vec2
+}
+
 #[derive(Component, Copy, Clone, Eq, PartialEq, Hash)]  // for spawning props as children when spawning entities
 pub enum PropIOU {
-    HoppyCube
+    HoppyCube,
+    Animal,
+    PineTree,
+    OakTree
 }
 
 #[derive(Component)]
@@ -21,7 +51,11 @@ pub struct PropPartMarker;
 pub struct HomegrownAssetsPlugin;
 impl Plugin for HomegrownAssetsPlugin {
     fn build(&self, app: &mut App) {
+        app.init_resource::<HopTuning>();
         app.add_event::<PropSpawn>();
+        app.add_event::<PropDespawn>();
+        app.add_event::<HopStarted>();
+        app.add_event::<HopLanded>();
         app.add_systems(PostUpdate, iou_consumer.before(prop_spawn_event_reader));
         app.add_systems(PostUpdate, prop_spawn_event_reader
                 .before(update_motion_trackers)
@@ -29,9 +63,15 @@ impl Plugin for HomegrownAssetsPlugin {
         );
         app.add_systems(Startup, assets_assets_setup);
         app.add_systems(PostUpdate, update_motion_trackers);
-        app.add_systems(Update, (
-            hoppy_cube::animate
-        ));
+        app.add_systems(PostUpdate, kill_reader.before(prop_despawn_event_reader));
+        app.add_systems(PostUpdate, prop_despawn_event_reader
+                .before(animate_dying_props)
+                .run_if(event_exists!(PropDespawn))
+        );
+        app.add_systems(PostUpdate, animate_dying_props);
+        app.add_systems(PostUpdate, release_removed_props);
+        app.init_resource::<PropPool>();
+        app.add_systems(Update, hoppy_cube::animate);
     }
 }
 
@@ -42,9 +82,14 @@ fn assets_assets_setup(
 ) {
     let (hoppy_mesh, hoppy_material)
         = hoppy_cube::generate_assets(&mut meshes, &mut materials);
+    let (animal_body_mesh, animal_head_mesh, animal_material)
+        = animal::generate_assets(&mut meshes, &mut materials);
+    let tree = tree::generate_assets(&mut meshes, &mut materials);
     commands.insert_resource(
         AssetsAssets {
-            hoppy_mesh, hoppy_material
+            hoppy_mesh, hoppy_material,
+            animal_body_mesh, animal_head_mesh, animal_material,
+            tree
         }
     );
 }
@@ -52,11 +97,26 @@ fn assets_assets_setup(
 #[derive(Resource)]
 struct AssetsAssets {
     hoppy_mesh: Handle<Mesh>,
-    hoppy_material: Handle<StandardMaterial>
+    hoppy_material: Handle<StandardMaterial>,
+    animal_body_mesh: Handle<Mesh>,
+    animal_head_mesh: Handle<Mesh>,
+    animal_material: Handle<StandardMaterial>,
+    tree: tree::TreeAssets
 }
 
 
 impl PropIOU {
+    pub const ALL: [Self; 4] = [Self::HoppyCube, Self::Animal, Self::PineTree, Self::OakTree];  // every registered prop, in preview order
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::HoppyCube => "hoppy_cube",
+            Self::Animal => "animal",
+            Self::PineTree => "pine_tree",
+            Self::OakTree => "oak_tree"
+        }
+    }
+
     fn spawn(
         &self,
         commands: &mut Commands,
@@ -64,7 +124,10 @@ impl PropIOU {
         parent: Entity
     ) {
         match self {
-            Self::HoppyCube => hoppy_cube::spawn(commands, parent, &assets_assets)
+            Self::HoppyCube => hoppy_cube::spawn(commands, parent, assets_assets),
+            Self::Animal => animal::spawn(commands, parent, assets_assets),
+            Self::PineTree => tree::spawn(commands, parent, assets_assets, tree::Canopy::Pine),
+            Self::OakTree => tree::spawn(commands, parent, assets_assets, tree::Canopy::Oak)
         }
     }
 }
@@ -92,22 +155,177 @@ fn iou_consumer(
 fn prop_spawn_event_reader(
     mut event_reader: EventReader<PropSpawn>,
     assets_assets: Res<AssetsAssets>,
+    mut pool: ResMut<PropPool>,
+    mut tracker_query: Query<(&mut MotionTracker, Option<&mut hoppy_cube::HoppyAnimation>)>,
+    mut part_transforms: Query<&mut Transform, With<PropPartMarker>>,
     mut commands: Commands
 ) {
     for event in event_reader.read() {
         let parent = if let Some(e) = event.entity {
             e
+        } else if let Some(e) = pool.take(event.iou) {
+            if let Ok((mut motion_tracker, hop)) = tracker_query.get_mut(e) {
+                motion_tracker.revive();
+                if let (Some(mut hop), Ok(mut transform)) = (hop, part_transforms.get_mut(motion_tracker.prop_parts[0])) {
+                    hop.revive(&mut transform);
+                };
+            };
+            commands.entity(e)
+                .remove::<PooledProp>()
+                .insert((
+                    event.transform.unwrap_or_default(),
+                    Visibility::Inherited
+                ));
+            continue;
         } else {
             commands.spawn((
                 event.transform.unwrap_or_default(),
+                Visibility::default(),
                 event.iou,
-                ConsumedIOU
+                ConsumedIOU,
+                Poolable
             )).id()
         };
         event.iou.spawn(&mut commands, &assets_assets, parent);
     }
 }
 
+#[derive(Component)]
+struct DyingProp {
+    remaining: f32,
+    duration: f32,
+    start_scale: Vec3
+}
+
+#[derive(Component)]  // standalone prop which is parked in `PropPool` instead of despawned
+struct Poolable;
+
+#[derive(Component)]  // parked in `PropPool`, hidden and skipped by animation
+struct PooledProp;
+
+#[derive(Resource, Default)]
+struct PropPool {
+    parked: HashMap<PropIOU, Vec<Entity>>
+} impl PropPool {
+    fn take(&mut self, iou: PropIOU) -> Option<Entity> {
+        self.parked.get_mut(&iou)?.pop()
+    }
+    fn park(&mut self, iou: PropIOU, entity: Entity) -> bool {
+        let parked = self.parked.entry(iou).or_default();
+        if parked.len() >= POOL_CAPACITY {
+            return false;
+        };
+        parked.push(entity);
+        true
+    }
+}
+
+fn release_prop(
+    commands: &mut Commands,
+    pool: &mut PropPool,
+    entity: Entity,
+    poolable: Option<PropIOU>
+) {
+    if let Some(iou) = poolable && pool.park(iou, entity) {
+        commands.entity(entity)
+            .remove::<DyingProp>()
+            .insert((PooledProp, Visibility::Hidden));
+        return;
+    };
+    commands.entity(entity).try_despawn();  // recursive, takes the prop parts with it
+}
+
+type KilledProps<'w, 's> = Query<'w, 's, (Entity, &'static MotionTracker), (Without<DyingProp>, Without<PooledProp>)>;
+
+fn kill_reader(
+    mut event_writer: EventWriter<PropDespawn>,
+    tracker_query: KilledProps
+) {
+    for (entity, motion_tracker) in tracker_query {
+        if motion_tracker.alive {
+            continue;
+        };
+        event_writer.write(
+            PropDespawn {
+                entity,
+                fade: Some(DEATH_FADE_SECONDS)
+            }
+        );
+    }
+}
+
+type DespawnableProps<'w, 's> = Query<'w, 's, (&'static Transform, Option<&'static PropIOU>, Has<Poolable>, Option<&'static DyingProp>), Without<PooledProp>>;
+
+fn prop_despawn_event_reader(
+    mut event_reader: EventReader<PropDespawn>,
+    prop_query: DespawnableProps,
+    mut pool: ResMut<PropPool>,
+    mut commands: Commands
+) {
+    for event in event_reader.read() {
+        let Ok((transform, iou, poolable, dying)) = prop_query.get(event.entity) else {
+            continue;
+        };
+        match event.fade {
+            Some(duration) if duration > 0.0 => {
+                commands.entity(event.entity).insert(
+                    DyingProp {
+                        remaining: duration,
+                        duration,
+                        start_scale: dying.map_or(transform.scale, |dying| dying.start_scale)  // already shrinking
+                    }
+                );
+            },
+            _ => {
+                let poolable = iou.copied().filter(|_| poolable);
+                release_prop(&mut commands, &mut pool, event.entity, poolable);
+            }
+        }
+    }
+}
+
+type DyingProps<'w, 's> = Query<'w, 's, (Entity, &'static mut Transform, &'static mut DyingProp, Option<&'static PropIOU>, Has<Poolable>)>;
+
+fn animate_dying_props(
+    time: Res<Time>,
+    dying_query: DyingProps,
+    mut pool: ResMut<PropPool>,
+    mut commands: Commands
+) {
+    let dt = time.delta_secs();
+    for (entity, mut transform, mut dying, iou, poolable) in dying_query {
+        dying.remaining -= dt;
+        let t = (dying.remaining / dying.duration).max(0.0);
+        transform.scale = dying.start_scale * t;
+        if dying.remaining <= 0.0 {
+            transform.scale = dying.start_scale;  // parked props come back at full size
+            let poolable = iou.copied().filter(|_| poolable);
+            release_prop(&mut commands, &mut pool, entity, poolable);
+        };
+    }
+}
+
+// Removing `PropIOU` from an entity that outlives it releases the parts
+// that were spawned for it.
+fn release_removed_props(
+    mut removed: RemovedComponents<PropIOU>,
+    tracker_query: Query<&MotionTracker>,
+    mut commands: Commands
+) {
+    for entity in removed.read() {
+        let Ok(motion_tracker) = tracker_query.get(entity) else {
+            continue;
+        };
+        for &part in &motion_tracker.prop_parts {
+            commands.entity(part).try_despawn();
+        };
+        commands.entity(entity).remove::<(MotionTracker, ConsumedIOU, Poolable, DyingProp)>();
+    }
+}
+
+// Tracks how a prop's parent moves between frames so its parts can be
+// animated procedurally. Wandering/behaviour state lives elsewhere
+// (see `animal::WanderTracker`).
 #[derive(Component)]
 pub struct MotionTracker {
     pub prop_parts: Vec<Entity>,
@@ -153,14 +371,27 @@ pub struct MotionTracker {
     pub fn reset(&mut self) {
         self.time = 0.0;
     }
+    pub fn revive(&mut self) {
+        self.velocity = Vec2::ZERO;
+        self.speed = 0.0;
+        self.time = 0.0;
+        self.moving = false;
+        self.initialized = false;
+        self.alive = true;
+    }
+    // Marks the prop for removal; it shrinks away over `DEATH_FADE_SECONDS`
+    // and is then despawned, or parked for reuse if it was spawned standalone.
     pub fn kill(&mut self) {
         self.alive = false;
     }
+    pub fn alive(&self) -> bool {
+        self.alive
+    }
 }
 
 fn update_motion_trackers(
     time: Res<Time>,
-    query: Query<(&Transform, &mut MotionTracker)>
+    query: Query<(&Transform, &mut MotionTracker), Without<PooledProp>>
 ) {
     let dt = time.delta_secs();
     for (transform, mut motion_tracker) in query {
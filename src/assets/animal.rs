@@ -0,0 +1,49 @@
+use bevy::prelude::*;
+use crate::assets::{AssetsAssets, MotionTracker, PropPartMarker};
+use crate::assets::hoppy_cube::HoppyAnimation;
+
+const BODY_RADIUS: f32 = 0.25;
+const HEAD_RADIUS: f32 = 0.125;
+const HEAD_OFFSET: Vec3 = Vec3::new(0.0, 0.25, 0.125);  // relative to the body, facing +y
+
+pub fn spawn(
+    commands: &mut Commands,
+    parent: Entity,
+    aa: &Res<AssetsAssets>
+) {
+    let body = commands.spawn((
+        Mesh3d(aa.animal_body_mesh.clone()),
+        MeshMaterial3d(aa.animal_material.clone()),
+        PropPartMarker,
+        ChildOf(parent),
+    )).id();
+    let head = commands.spawn((
+        Mesh3d(aa.animal_head_mesh.clone()),
+        MeshMaterial3d(aa.animal_material.clone()),
+        Transform::from_translation(HEAD_OFFSET),
+        PropPartMarker,
+        ChildOf(body),
+    )).id();
+    commands.entity(parent).insert(
+        (
+            MotionTracker::new(vec![body, head]),
+            HoppyAnimation::new(BODY_RADIUS)
+        )
+    );
+}
+
+pub fn generate_assets(
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>
+) -> (Handle<Mesh>, Handle<Mesh>, Handle<StandardMaterial>) {
+    let material = materials.add(
+        StandardMaterial {
+            base_color: Color::linear_rgb(0.0, 1.0, 0.0),
+            perceptual_roughness: 0.8,
+            ..default()
+        }
+    );
+    let body_mesh = meshes.add(Sphere::new(BODY_RADIUS));
+    let head_mesh = meshes.add(Sphere::new(HEAD_RADIUS));
+    (body_mesh, head_mesh, material)
+}
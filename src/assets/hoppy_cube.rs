@@ -1,12 +1,33 @@
 use bevy::prelude::*;
 use std::f32::consts::PI;
-use crate::assets::{AssetsAssets, MotionTracker, PropPartMarker};
+use serde::Deserialize;
+use crate::assets::{AssetsAssets, HopLanded, HopStarted, MotionTracker, PooledProp, PropPartMarker};
 
 const SPIN: f32 = -PI;
 const Z: f32 = 0.5;
 const BOUNCE_SPEED: f32 = 8.0;
 const HOP_HEIGHT: f32 = 1.0;
 
+#[derive(Resource, Clone, Deserialize)]
+#[serde(default)]
+pub struct HopTuning {
+    pub spin: f32,
+    pub bounce_speed: f32,
+    pub hop_height: f32
+} impl HopTuning {
+    fn hop_duration(&self) -> f32 {  // seconds from take-off to landing
+        PI / self.bounce_speed
+    }
+} impl Default for HopTuning {
+    fn default() -> Self {
+        Self {
+            spin: SPIN,
+            bounce_speed: BOUNCE_SPEED,
+            hop_height: HOP_HEIGHT
+        }
+    }
+}
+
 pub fn spawn(
     commands: &mut Commands,
     parent: Entity,
@@ -21,7 +42,7 @@ pub fn spawn(
     commands.entity(parent).insert(
         (
             MotionTracker::new(vec![entity]),
-            HoppyAnimation
+            HoppyAnimation::new(Z)
         )
     );
 }
@@ -43,23 +64,65 @@ pub fn generate_assets(
 }
 
 #[derive(Component)]
-pub struct HoppyAnimation;
+pub struct HoppyAnimation {
+    rest_z: f32,  // height of the first prop part while grounded
+    phase: f32,  // 0.0 is grounded, 1.0 is landing
+    airborne: bool
+} impl HoppyAnimation {
+    pub fn new(rest_z: f32) -> Self {
+        Self {
+            rest_z,
+            phase: 0.0,
+            airborne: false
+        }
+    }
+    // Lands a prop taken back from the pool, so it doesn't finish a hop
+    // from its previous life.
+    pub fn revive(&mut self, part: &mut Transform) {
+        self.phase = 0.0;
+        self.airborne = false;
+        part.translation.z = self.rest_z;
+        part.rotation = Quat::IDENTITY;
+    }
+}
 
 pub fn animate(
-    motion_query: Query<&MotionTracker, With<HoppyAnimation>>,
-    mut part_transforms: Query<&mut Transform, With<PropPartMarker>>
+    time: Res<Time>,
+    tuning: Res<HopTuning>,
+    motion_query: Query<(Entity, &MotionTracker, &mut HoppyAnimation), Without<PooledProp>>,
+    mut part_transforms: Query<&mut Transform, With<PropPartMarker>>,
+    mut started_writer: EventWriter<HopStarted>,
+    mut landed_writer: EventWriter<HopLanded>
 ) {
-    for motion_tracker in motion_query {
-        let mut transform = part_transforms.get_mut(motion_tracker.prop_parts[0]).unwrap();
-        if motion_tracker.moving {
-            transform.translation.z = Z + (motion_tracker.time * BOUNCE_SPEED).sin().abs() * HOP_HEIGHT;
-            if motion_tracker.speed > 1.0 {
-                transform.rotate_z(motion_tracker.dt * SPIN * motion_tracker.speed);
+    let dt = time.delta_secs();
+    for (entity, motion_tracker, mut hop) in motion_query {
+        let position = motion_tracker.last_pos;
+        if !hop.airborne {
+            if !motion_tracker.moving {
+                continue;
+            };
+            hop.airborne = true;
+            hop.phase = 0.0;
+            started_writer.write(HopStarted {entity, position});
+        };
+        hop.phase += dt / tuning.hop_duration();
+        if hop.phase >= 1.0 {
+            landed_writer.write(HopLanded {entity, position});
+            if motion_tracker.moving {  // carry the overshoot into the next hop
+                hop.phase = hop.phase.fract();
+                started_writer.write(HopStarted {entity, position});
             } else {
-                transform.rotate_z(motion_tracker.dt * SPIN);
+                hop.phase = 0.0;
+                hop.airborne = false;
             };
-        } else {
-            transform.translation.z = Z;
-        }
+        };
+        let Ok(mut transform) = part_transforms.get_mut(motion_tracker.prop_parts[0]) else {
+            continue;
+        };
+        // Relative to the parent, which a `ground::GroundFollower` keeps on the terrain.
+        transform.translation.z = hop.rest_z + (hop.phase * PI).sin() * tuning.hop_height;
+        if hop.airborne {
+            transform.rotate_z(dt * tuning.spin * motion_tracker.speed.max(1.0));
+        };
     }
-}
\ No newline at end of file
+}
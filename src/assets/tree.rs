@@ -0,0 +1,102 @@
+use std::f32::consts::FRAC_PI_2;
+use bevy::prelude::*;
+use crate::assets::{AssetsAssets, PropPartMarker};
+
+const STUMP_RADIUS: f32 = 0.18;
+const STUMP_HEIGHT: f32 = 0.2;
+const TRUNK_RADIUS: f32 = 0.12;
+const TRUNK_HEIGHT: f32 = 1.2;
+const PINE_RADIUS: f32 = 0.6;
+const PINE_HEIGHT: f32 = 1.6;
+const PINE_BASE: f32 = 0.5;  // height up the trunk at which the cone starts
+const OAK_RADIUS: f32 = 0.7;
+
+// Pivot at the top of the stump that the trunk and canopy hang from, so a
+// tree can sway, shake and fall over while its stump stays put.
+#[derive(Component)]
+pub struct TreeCrown;
+
+pub struct TreeAssets {
+    stump_mesh: Handle<Mesh>,
+    trunk_mesh: Handle<Mesh>,
+    pine_mesh: Handle<Mesh>,
+    oak_mesh: Handle<Mesh>,
+    bark_material: Handle<StandardMaterial>,
+    stump_material: Handle<StandardMaterial>,
+    pine_material: Handle<StandardMaterial>,
+    oak_material: Handle<StandardMaterial>
+}
+
+#[derive(Copy, Clone)]
+pub enum Canopy {
+    Pine,
+    Oak
+}
+
+// Meshes are built along +y, the world is z-up.
+fn upright() -> Quat {
+    Quat::from_rotation_x(FRAC_PI_2)
+}
+
+pub fn spawn(
+    commands: &mut Commands,
+    parent: Entity,
+    aa: &Res<AssetsAssets>,
+    canopy: Canopy
+) {
+    let ta = &aa.tree;
+    commands.spawn((
+        Mesh3d(ta.stump_mesh.clone()),
+        MeshMaterial3d(ta.stump_material.clone()),
+        Transform::from_translation(Vec3::Z * STUMP_HEIGHT * 0.5).with_rotation(upright()),
+        PropPartMarker,
+        ChildOf(parent),
+    ));
+    let crown = commands.spawn((
+        Transform::from_translation(Vec3::Z * STUMP_HEIGHT),
+        Visibility::default(),
+        TreeCrown,
+        ChildOf(parent),
+    )).id();
+    commands.spawn((
+        Mesh3d(ta.trunk_mesh.clone()),
+        MeshMaterial3d(ta.bark_material.clone()),
+        Transform::from_translation(Vec3::Z * TRUNK_HEIGHT * 0.5).with_rotation(upright()),
+        PropPartMarker,
+        ChildOf(crown),
+    ));
+    let (mesh, material, translation) = match canopy {
+        Canopy::Pine => (&ta.pine_mesh, &ta.pine_material, Vec3::Z * (PINE_BASE + PINE_HEIGHT * 0.5)),
+        Canopy::Oak => (&ta.oak_mesh, &ta.oak_material, Vec3::Z * (TRUNK_HEIGHT + OAK_RADIUS * 0.5))
+    };
+    commands.spawn((
+        Mesh3d(mesh.clone()),
+        MeshMaterial3d(material.clone()),
+        Transform::from_translation(translation).with_rotation(upright()),
+        PropPartMarker,
+        ChildOf(crown),
+    ));
+}
+
+pub fn generate_assets(
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>
+) -> TreeAssets {
+    let mut material = |r: f32, g: f32, b: f32| materials.add(
+        StandardMaterial {
+            base_color: Color::linear_rgb(r, g, b),
+            perceptual_roughness: 0.9,
+            ..default()
+        }
+    );
+    TreeAssets {
+        bark_material: material(0.25, 0.14, 0.07),
+        stump_material: material(0.45, 0.3, 0.15),
+        pine_material: material(0.05, 0.3, 0.1),
+        oak_material: material(0.2, 0.5, 0.1),
+        stump_mesh: meshes.add(Cylinder::new(STUMP_RADIUS, STUMP_HEIGHT)),
+        trunk_mesh: meshes.add(Cylinder::new(TRUNK_RADIUS, TRUNK_HEIGHT)),
+        pine_mesh: meshes.add(Cone {radius: PINE_RADIUS, height: PINE_HEIGHT}),
+        oak_mesh: meshes.add(Sphere::new(OAK_RADIUS))
+    }
+}
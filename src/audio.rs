@@ -0,0 +1,400 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::Path;
+use bevy::prelude::*;
+use rodio::buffer::SamplesBuffer;
+use rodio::mixer::Mixer;
+use rodio::source::ChannelVolume;
+use rodio::{Decoder, OutputStreamBuilder, Sink, Source};
+use crate::animal;
+use crate::assets::{HopLanded, MotionTracker};
+use crate::camera_module::CameraPos;
+
+mod music;
+mod spatial;
+mod synth;
+
+pub use music::Tension;
+
+pub use spatial::{SoundEmitter, SpatialAudio};
+pub use synth::{SynthSource, Voice, Waveform};
+
+const SOUND_DIRECTORY: &str = "assets/sounds";
+const MASTER_VOLUME: f32 = 1.0;
+const MUSIC_VOLUME: f32 = 0.5;
+const SFX_VOLUME: f32 = 1.0;
+const HOP_VOLUME: f32 = 0.25;
+const IMPACT_VOLUME: f32 = 1.0;
+const CHIRP_VOLUME: f32 = 0.3;
+
+// Decoded samples, cloning shares the underlying buffer.
+pub type SoundClip = SamplesBuffer;
+
+// Sounds loaded from files. Hops, impacts and calls are synthesized, see `Voice`.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Sound {
+    Ambient,
+    MusicBase,  // music stems are layered by `Tension`
+    MusicDrums,
+    MusicLead,
+    GameOverCue
+} impl Sound {
+    pub const ALL: [Self; 5] = [
+        Self::Ambient,
+        Self::MusicBase,
+        Self::MusicDrums,
+        Self::MusicLead,
+        Self::GameOverCue
+    ];
+
+    fn file_name(&self) -> &'static str {
+        match self {
+            Self::Ambient => "ambient.ogg",
+            Self::MusicBase => "music_base.ogg",
+            Self::MusicDrums => "music_drums.ogg",
+            Self::MusicLead => "music_lead.ogg",
+            Self::GameOverCue => "game_over.ogg"
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Bus {
+    Music,
+    Sfx
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Default)]
+pub enum AudioBackend {
+    #[default]
+    Device,  // the default output device, falls back to `Null` if none opens
+    Null  // nothing is heard, for headless runs and tests
+}
+
+#[derive(Event, Copy, Clone)]  // one-shot on the sfx bus
+pub struct PlaySound {
+    pub sound: Sound,
+    pub volume: f32,
+    pub emitter: Option<Entity>  // placed in the world if this has a `SoundEmitter`
+}
+
+#[derive(Event, Copy, Clone)]  // one-shot synthesized on the sfx bus
+pub struct PlayVoice {
+    pub voice: Voice,
+    pub volume: f32,
+    pub emitter: Option<Entity>  // placed in the world if this has a `SoundEmitter`
+}
+
+#[derive(Event, Copy, Clone)]  // loops until a matching `StopTrack`
+pub struct PlayTrack {
+    pub sound: Sound,
+    pub bus: Bus,
+    pub volume: f32  // scaled by the bus, see `LoopingTracks::set_volume`
+}
+
+#[derive(Event, Copy, Clone)]
+pub struct StopTrack {
+    pub sound: Sound
+}
+
+#[derive(Resource, Copy, Clone)]
+pub struct AudioVolumes {
+    pub master: f32,
+    pub music: f32,
+    pub sfx: f32
+} impl AudioVolumes {
+    pub fn gain(&self, bus: Bus) -> f32 {
+        self.master * match bus {
+            Bus::Music => self.music,
+            Bus::Sfx => self.sfx
+        }
+    }
+} impl Default for AudioVolumes {
+    fn default() -> Self {
+        Self {
+            master: MASTER_VOLUME,
+            music: MUSIC_VOLUME,
+            sfx: SFX_VOLUME
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct SoundBank {
+    clips: HashMap<Sound, SoundClip>
+} impl SoundBank {
+    pub fn get(&self, sound: Sound) -> Option<&SoundClip> {
+        self.clips.get(&sound)
+    }
+    pub fn insert(&mut self, sound: Sound, clip: SoundClip) {
+        self.clips.insert(sound, clip);
+    }
+}
+
+// Collects a finite source into a clip that can be replayed cheaply.
+pub fn clip_from_source(source: impl Source) -> SoundClip {
+    let channels = source.channels();
+    let sample_rate = source.sample_rate();
+    SamplesBuffer::new(channels, sample_rate, source.collect::<Vec<f32>>())
+}
+
+#[derive(Resource)]
+pub struct AudioOutput {
+    mixer: Option<Mixer>  // `None` for the null backend
+} impl AudioOutput {
+    pub fn mixer(&self) -> Option<&Mixer> {
+        self.mixer.as_ref()
+    }
+}
+
+struct LoopingTrack {
+    bus: Bus,
+    volume: f32,
+    sink: Option<Sink>
+}
+
+#[derive(Resource, Default)]
+pub struct LoopingTracks {
+    tracks: HashMap<Sound, LoopingTrack>
+} impl LoopingTracks {
+    pub fn is_playing(&self, sound: Sound) -> bool {
+        self.tracks.contains_key(&sound)
+    }
+    pub fn set_volume(&mut self, sound: Sound, volume: f32, volumes: &AudioVolumes) {
+        let Some(track) = self.tracks.get_mut(&sound) else {
+            return;
+        };
+        track.volume = volume;
+        if let Some(sink) = &track.sink {
+            sink.set_volume(volume * volumes.gain(track.bus));
+        };
+    }
+}
+
+pub struct AudioPlugin {
+    pub backend: AudioBackend
+}
+impl Plugin for AudioPlugin {
+    fn build(&self, app: &mut App) {
+        let mixer = match self.backend {
+            AudioBackend::Null => None,
+            AudioBackend::Device => match OutputStreamBuilder::open_default_stream() {
+                Ok(stream) => {
+                    let mixer = stream.mixer().clone();
+                    app.insert_non_send_resource(stream);  // dropping the stream silences it
+                    Some(mixer)
+                },
+                Err(e) => {
+                    warn!("no audio device, continuing silently: {e}");
+                    None
+                }
+            }
+        };
+        app.insert_resource(AudioOutput {mixer});
+        app.init_resource::<AudioVolumes>();
+        app.init_resource::<SpatialAudio>();
+        app.init_resource::<SoundBank>();
+        app.init_resource::<LoopingTracks>();
+        app.init_resource::<Tension>();
+        app.init_resource::<music::MusicMix>();
+        app.add_event::<PlaySound>();
+        app.add_event::<PlayVoice>();
+        app.add_event::<PlayTrack>();
+        app.add_event::<StopTrack>();
+        app.add_event::<HopLanded>();  // also registered by `assets::HomegrownAssetsPlugin`
+        app.add_event::<animal::ImpactEvent>();  // also registered by `animal::Plugin`
+        app.add_event::<animal::WanderEvent>();  // likewise
+        app.add_systems(Startup, load_sound_bank);
+        app.add_systems(Startup, start_default_tracks.after(load_sound_bank));
+        app.add_systems(Startup, music::start_stems.after(load_sound_bank));
+        app.add_systems(Update, (music::compute_tension, music::mix_stems).chain());
+        app.add_systems(Update, music::game_over_cue.before(music::mix_stems));
+        app.add_systems(Update, (hop_sounds, impact_sounds, wander_sounds));
+        app.add_systems(PostUpdate, (play_sounds, play_voices));
+        app.add_systems(PostUpdate, (play_tracks, stop_tracks));
+        app.add_systems(PostUpdate, apply_volumes.run_if(resource_changed::<AudioVolumes>));
+    }
+}
+
+fn load_clip(path: &Path) -> Result<SoundClip, String> {
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    let decoder = Decoder::try_from(file).map_err(|e| e.to_string())?;
+    Ok(clip_from_source(decoder))
+}
+
+fn load_sound_bank(
+    mut bank: ResMut<SoundBank>
+) {
+    for sound in Sound::ALL {
+        let path = Path::new(SOUND_DIRECTORY).join(sound.file_name());
+        match load_clip(&path) {
+            Ok(clip) => bank.insert(sound, clip),
+            Err(e) => warn!("cannot load {}: {e}", path.display())
+        }
+    }
+}
+
+fn start_default_tracks(
+    mut event_writer: EventWriter<PlayTrack>
+) {
+    event_writer.write(PlayTrack {sound: Sound::Ambient, bus: Bus::Sfx, volume: 1.0});
+}
+
+fn hop_sounds(
+    mut event_reader: EventReader<HopLanded>,
+    tracker_query: Query<&MotionTracker>,
+    mut event_writer: EventWriter<PlayVoice>
+) {
+    for event in event_reader.read() {
+        let speed = tracker_query.get(event.entity).map_or(0.0, |t| t.speed);
+        event_writer.write(PlayVoice {
+            voice: Voice::hop(event.entity.to_bits() ^ ::fastrand::u64(..), speed),
+            volume: HOP_VOLUME,
+            emitter: Some(event.entity)
+        });
+    }
+}
+
+fn impact_sounds(
+    mut event_reader: EventReader<animal::ImpactEvent>,
+    mut event_writer: EventWriter<PlayVoice>
+) {
+    for event in event_reader.read() {
+        event_writer.write(PlayVoice {
+            voice: Voice::thud(event.tree.to_bits() ^ ::fastrand::u64(..)),
+            volume: IMPACT_VOLUME,
+            emitter: Some(event.tree)
+        });
+    }
+}
+
+fn wander_sounds(
+    mut event_reader: EventReader<animal::WanderEvent>,
+    species_query: Query<&animal::Species>,
+    mut event_writer: EventWriter<PlayVoice>
+) {
+    for event in event_reader.read() {
+        let Ok(&animal::Species(species)) = species_query.get(event.entity) else {
+            continue;
+        };
+        event_writer.write(PlayVoice {
+            voice: Voice::chirp(species as u64),
+            volume: CHIRP_VOLUME,
+            emitter: Some(event.entity)
+        });
+    }
+}
+
+enum Placement {
+    Centred,
+    Stereo([f32; 2]),
+    Inaudible
+}
+
+fn place(
+    emitter: Option<Entity>,
+    emitter_query: &Query<(&GlobalTransform, &SoundEmitter)>,
+    spatial: &SpatialAudio,
+    listener: Vec2
+) -> Placement {
+    let Some((transform, emitter)) = emitter.and_then(|e| emitter_query.get(e).ok()) else {
+        return Placement::Centred;
+    };
+    match spatial.stereo_gains(listener, transform.translation().xy()) {
+        Some([left, right]) => Placement::Stereo([left * emitter.volume, right * emitter.volume]),
+        None => Placement::Inaudible
+    }
+}
+
+fn mix_one_shot<S>(mixer: &Mixer, source: S, gain: f32, placement: Placement)
+where
+    S: Source + Send + 'static {
+    match placement {
+        Placement::Centred => mixer.add(source.amplify(gain)),
+        Placement::Stereo([left, right]) => mixer.add(ChannelVolume::new(source, vec![left * gain, right * gain])),
+        Placement::Inaudible => {}
+    }
+}
+
+fn play_sounds(
+    mut event_reader: EventReader<PlaySound>,
+    output: Res<AudioOutput>,
+    bank: Res<SoundBank>,
+    volumes: Res<AudioVolumes>,
+    spatial: Res<SpatialAudio>,
+    camera_pos: Option<Res<CameraPos>>,
+    emitter_query: Query<(&GlobalTransform, &SoundEmitter)>
+) {
+    let listener = camera_pos.map_or(Vec2::ZERO, |c| c.vec2);
+    for event in event_reader.read() {
+        let (Some(mixer), Some(clip)) = (output.mixer(), bank.get(event.sound)) else {
+            continue;
+        };
+        let placement = place(event.emitter, &emitter_query, &spatial, listener);
+        mix_one_shot(mixer, clip.clone(), event.volume * volumes.gain(Bus::Sfx), placement);
+    }
+}
+
+fn play_voices(
+    mut event_reader: EventReader<PlayVoice>,
+    output: Res<AudioOutput>,
+    volumes: Res<AudioVolumes>,
+    spatial: Res<SpatialAudio>,
+    camera_pos: Option<Res<CameraPos>>,
+    emitter_query: Query<(&GlobalTransform, &SoundEmitter)>
+) {
+    let listener = camera_pos.map_or(Vec2::ZERO, |c| c.vec2);
+    for event in event_reader.read() {
+        let Some(mixer) = output.mixer() else {
+            continue;
+        };
+        let placement = place(event.emitter, &emitter_query, &spatial, listener);
+        mix_one_shot(mixer, event.voice.source(), event.volume * volumes.gain(Bus::Sfx), placement);
+    }
+}
+
+fn play_tracks(
+    mut event_reader: EventReader<PlayTrack>,
+    output: Res<AudioOutput>,
+    bank: Res<SoundBank>,
+    volumes: Res<AudioVolumes>,
+    mut tracks: ResMut<LoopingTracks>
+) {
+    for event in event_reader.read() {
+        if tracks.is_playing(event.sound) {
+            continue;
+        };
+        let Some(clip) = bank.get(event.sound) else {
+            continue;
+        };
+        let sink = output.mixer().map(|mixer| {
+            let sink = Sink::connect_new(mixer);
+            sink.set_volume(event.volume * volumes.gain(event.bus));
+            sink.append(clip.clone().repeat_infinite());
+            sink
+        });
+        tracks.tracks.insert(event.sound, LoopingTrack {bus: event.bus, volume: event.volume, sink});
+    }
+}
+
+fn stop_tracks(
+    mut event_reader: EventReader<StopTrack>,
+    mut tracks: ResMut<LoopingTracks>
+) {
+    for event in event_reader.read() {
+        if let Some(LoopingTrack {sink: Some(sink), ..}) = tracks.tracks.remove(&event.sound) {
+            sink.stop();
+        };
+    }
+}
+
+fn apply_volumes(
+    volumes: Res<AudioVolumes>,
+    tracks: Res<LoopingTracks>
+) {
+    for track in tracks.tracks.values() {
+        if let Some(sink) = &track.sink {
+            sink.set_volume(track.volume * volumes.gain(track.bus));
+        };
+    }
+}
@@ -0,0 +1,154 @@
+use bevy::prelude::*;
+use crate::animal::{self, DeathLifecycle, DeathTracker};
+use crate::audio::{AudioOutput, AudioVolumes, Bus, LoopingTracks, PlayTrack, Sound, SoundBank, StopTrack};
+use crate::camera_module::VIEW_WIDTH;
+use crate::player_controls::PlayerMotion;
+use crate::streaming::WorldChunks;
+use rodio::Source;
+
+const CROSSFADE_SECONDS: f32 = 2.0;  // time for a stem to fade fully in or out
+const DANGER_RADIUS: f32 = VIEW_WIDTH;  // ramming animals closer than this raise tension
+const RAMMING_WEIGHT: f32 = 0.5;
+const TIMEOUT_WEIGHT: f32 = 0.25;  // animals nearing their death timeout raise tension
+const PROXIMITY_WEIGHT: f32 = 0.25;
+
+// Each stem fades in as tension rises through its window.
+struct Stem {
+    sound: Sound,
+    fade_in_from: f32,
+    fade_in_to: f32
+} impl Stem {
+    fn level(&self, tension: f32) -> f32 {
+        let t = ((tension - self.fade_in_from) / (self.fade_in_to - self.fade_in_from)).clamp(0.0, 1.0);
+        t * t * (3.0 - 2.0 * t)  // smoothstep
+    }
+}
+
+const STEMS: [Stem; 3] = [
+    Stem {sound: Sound::MusicBase, fade_in_from: -1.0, fade_in_to: 0.0},
+    Stem {sound: Sound::MusicDrums, fade_in_from: 0.25, fade_in_to: 0.5},
+    Stem {sound: Sound::MusicLead, fade_in_from: 0.6, fade_in_to: 0.9}
+];
+
+// 0.0 is calm, 1.0 is as tense as the simulation gets.
+#[derive(Resource, Default)]
+pub struct Tension {
+    pub value: f32
+}
+
+#[derive(Resource, Default)]
+pub(super) struct MusicMix {
+    levels: [f32; STEMS.len()],
+    seen_alive: bool,  // any animal has lived, so an empty world means they all died
+    game_over: bool,
+    stopped: bool
+}
+
+pub(super) fn start_stems(
+    mut event_writer: EventWriter<PlayTrack>
+) {
+    for stem in &STEMS {  // started together so the loops stay in sync
+        event_writer.write(PlayTrack {sound: stem.sound, bus: Bus::Music, volume: stem.level(0.0)});
+    }
+}
+
+pub(super) fn compute_tension(
+    animal_query: Query<(&Transform, &DeathTracker)>,
+    tuning: Option<Res<animal::Tuning>>,
+    player_motion: Option<Res<PlayerMotion>>,
+    mut tension: ResMut<Tension>
+) {
+    let mut animals = 0usize;
+    let mut ramming = 0usize;
+    let mut nearest_danger = f32::INFINITY;
+    let mut soonest_timeout = f32::INFINITY;
+    let player = player_motion.map(|p| p.translation);
+    for (transform, tracker) in animal_query {
+        animals += 1;
+        match tracker.lifecycle {
+            DeathLifecycle::Alive => soonest_timeout = soonest_timeout.min(tracker.timeout),
+            DeathLifecycle::Ramming(_) => {
+                ramming += 1;
+                if let Some(p) = player {
+                    nearest_danger = nearest_danger.min(p.distance(transform.translation.xy()));
+                };
+            },
+            _ => {}
+        }
+    }
+    let ramming_fraction = if animals > 0 {
+        ramming as f32 / animals as f32
+    } else {
+        0.0
+    };
+    let max_timeout = tuning.map_or(animal::MAX_DEATH_TIMEOUT_SECONDS, |t| t.max_death_timeout_seconds);
+    let time_pressure = (1.0 - soonest_timeout / max_timeout).clamp(0.0, 1.0);
+    let proximity = (1.0 - nearest_danger / DANGER_RADIUS).max(0.0);
+    tension.value = (
+        ramming_fraction * RAMMING_WEIGHT
+        + time_pressure * TIMEOUT_WEIGHT
+        + proximity * PROXIMITY_WEIGHT
+    ).clamp(0.0, 1.0);
+}
+
+pub(super) fn mix_stems(
+    time: Res<Time>,
+    tension: Res<Tension>,
+    volumes: Res<AudioVolumes>,
+    mut mix: ResMut<MusicMix>,
+    mut tracks: ResMut<LoopingTracks>,
+    mut stop_writer: EventWriter<StopTrack>
+) {
+    if mix.stopped {
+        return;
+    };
+    let step = time.delta_secs() / CROSSFADE_SECONDS;
+    let game_over = mix.game_over;
+    for (level, stem) in mix.levels.iter_mut().zip(&STEMS) {
+        let target = if game_over {
+            0.0
+        } else {
+            stem.level(tension.value)
+        };
+        *level += (target - *level).clamp(-step, step);
+        tracks.set_volume(stem.sound, *level, &volumes);
+    }
+    if game_over && mix.levels.iter().all(|&level| level <= 0.0) {
+        for stem in &STEMS {
+            stop_writer.write(StopTrack {sound: stem.sound});
+        }
+        mix.stopped = true;
+    };
+}
+
+// Fades the stems out and plays the game over cue over them once every
+// animal has died, counting those saved with chunks out of range as well
+// as the loaded ones.
+pub(super) fn game_over_cue(
+    animal_query: Query<&DeathTracker>,
+    chunks: Option<Res<WorldChunks>>,
+    output: Res<AudioOutput>,
+    bank: Res<SoundBank>,
+    volumes: Res<AudioVolumes>,
+    mut mix: ResMut<MusicMix>
+) {
+    if mix.game_over {
+        return;
+    };
+    let loaded = animal_query
+        .iter()
+        .filter(|tracker| !matches!(tracker.lifecycle, DeathLifecycle::Corpse))  // still alive, or still dying
+        .count();
+    let living = loaded + chunks.map_or(0, |chunks| chunks.saved_living_animals());
+    if living > 0 {
+        mix.seen_alive = true;
+        return;
+    };
+    if !mix.seen_alive {
+        return;
+    };
+    mix.game_over = true;
+    if let (Some(mixer), Some(clip)) = (output.mixer(), bank.get(Sound::GameOverCue)) {
+        mixer.add(clip.clone().amplify(volumes.gain(Bus::Music)));
+    };
+}
@@ -0,0 +1,59 @@
+use bevy::prelude::*;
+use crate::camera_module::VIEW_WIDTH;
+
+const REFERENCE_DISTANCE: f32 = VIEW_WIDTH * 0.5;  // heard at full volume up to here
+const MAX_AUDIBLE_DISTANCE: f32 = VIEW_WIDTH * 2.0;  // silent from here on
+const ROLLOFF: f32 = 2.0;  // falloff curve exponent, 1.0 is linear
+const PAN_WIDTH: f32 = VIEW_WIDTH * 0.5;  // horizontal offset at which a sound is fully on one side
+
+// Marks an entity whose one-shots are placed in the world relative to the
+// camera instead of playing centred.
+#[derive(Component, Copy, Clone)]
+pub struct SoundEmitter {
+    pub volume: f32
+} impl Default for SoundEmitter {
+    fn default() -> Self {
+        Self {
+            volume: 1.0
+        }
+    }
+}
+
+#[derive(Resource, Copy, Clone)]
+pub struct SpatialAudio {
+    pub reference_distance: f32,
+    pub max_distance: f32,
+    pub rolloff: f32,
+    pub pan_width: f32
+} impl SpatialAudio {
+    pub fn attenuation(&self, distance: f32) -> f32 {
+        if distance >= self.max_distance {
+            return 0.0;
+        };
+        let range = (self.max_distance - self.reference_distance).max(f32::EPSILON);
+        let t = ((distance - self.reference_distance) / range).clamp(0.0, 1.0);
+        (1.0 - t).powf(self.rolloff)
+    }
+    // Left/right gains of a sound at `emitter` heard from `listener`, `None`
+    // when out of range. The camera never rotates, so world +x is screen right.
+    pub fn stereo_gains(&self, listener: Vec2, emitter: Vec2) -> Option<[f32; 2]> {
+        let attenuation = self.attenuation(listener.distance(emitter));
+        if attenuation <= 0.0 {
+            return None;
+        };
+        let pan = ((emitter.x - listener.x) / self.pan_width).clamp(-1.0, 1.0);
+        Some([
+            attenuation * (1.0 - pan).min(1.0),
+            attenuation * (1.0 + pan).min(1.0)
+        ])
+    }
+} impl Default for SpatialAudio {
+    fn default() -> Self {
+        Self {
+            reference_distance: REFERENCE_DISTANCE,
+            max_distance: MAX_AUDIBLE_DISTANCE,
+            rolloff: ROLLOFF,
+            pan_width: PAN_WIDTH
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use std::f32::consts::TAU;
+use std::time::Duration;
+use fastrand::Rng;
+use rodio::{ChannelCount, SampleRate, Source};
+
+const SAMPLE_RATE: SampleRate = 44_100;
+const ATTACK_SECONDS: f32 = 0.005;  // short fade in so voices don't click
+
+#[derive(Copy, Clone)]
+pub enum Waveform {
+    Sine,
+    Square,
+    Triangle
+} impl Waveform {
+    fn sample(&self, phase: f32) -> f32 {
+        match self {
+            Self::Sine => (phase * TAU).sin(),
+            Self::Square => if phase < 0.5 { 1.0 } else { -1.0 },
+            Self::Triangle => 4.0 * (phase - 0.5).abs() - 1.0
+        }
+    }
+}
+
+// Parameters of one synthesized sound. Presets derive these from a seed so
+// the same seed always sounds the same.
+#[derive(Copy, Clone)]
+pub struct Voice {
+    pub waveform: Waveform,
+    pub seconds: f32,
+    pub start_hz: f32,
+    pub end_hz: f32,  // swept exponentially from `start_hz`
+    pub noise: f32,  // 0.0 is a pure tone, 1.0 is pure (filtered) noise
+    pub noise_cutoff: f32,  // 0.0..1.0, lower is duller
+    pub decay: f32,  // envelope exponent, higher dies away faster
+    pub seed: u64
+} impl Voice {
+    // Pitch-swept blip, rising more steeply the faster the hopper moves.
+    pub fn hop(seed: u64, speed: f32) -> Self {
+        let mut rng = Rng::with_seed(seed);
+        let start_hz = 260.0 + rng.f32() * 160.0;
+        Self {
+            waveform: Waveform::Square,
+            seconds: 0.08,
+            start_hz,
+            end_hz: start_hz * (1.5 + speed * 0.25).min(4.0),
+            noise: 0.0,
+            noise_cutoff: 1.0,
+            decay: 2.0,
+            seed
+        }
+    }
+    // Low noisy thump, as of something heavy hitting a tree.
+    pub fn thud(seed: u64) -> Self {
+        let mut rng = Rng::with_seed(seed);
+        Self {
+            waveform: Waveform::Sine,
+            seconds: 0.2 + rng.f32() * 0.1,
+            start_hz: 110.0 + rng.f32() * 40.0,
+            end_hz: 40.0,
+            noise: 0.6,
+            noise_cutoff: 0.08,
+            decay: 3.0,
+            seed
+        }
+    }
+    // Short bird-like call, one recognisable call per species.
+    pub fn chirp(species: u64) -> Self {
+        let mut rng = Rng::with_seed(species);
+        let start_hz = 1_400.0 + rng.f32() * 1_600.0;
+        let ratio = if rng.bool() { 1.3 + rng.f32() * 0.5 } else { 0.6 + rng.f32() * 0.2 };
+        Self {
+            waveform: Waveform::Triangle,
+            seconds: 0.1 + rng.f32() * 0.1,
+            start_hz,
+            end_hz: start_hz * ratio,
+            noise: 0.05,
+            noise_cutoff: 0.5,
+            decay: 1.5,
+            seed: species
+        }
+    }
+    pub fn source(self) -> SynthSource {
+        SynthSource {
+            length: (self.seconds * SAMPLE_RATE as f32) as usize,
+            index: 0,
+            phase: 0.0,
+            filtered_noise: 0.0,
+            rng: Rng::with_seed(self.seed),
+            voice: self
+        }
+    }
+}
+
+// Mono rodio source rendering a `Voice` sample by sample.
+pub struct SynthSource {
+    voice: Voice,
+    length: usize,
+    index: usize,
+    phase: f32,
+    filtered_noise: f32,
+    rng: Rng
+}
+
+impl Iterator for SynthSource {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.index >= self.length {
+            return None;
+        };
+        let voice = &self.voice;
+        let t = self.index as f32 / self.length as f32;
+        let hz = voice.start_hz * (voice.end_hz / voice.start_hz).powf(t);
+        self.phase = (self.phase + hz / SAMPLE_RATE as f32).fract();
+        let tone = voice.waveform.sample(self.phase);
+        let white = self.rng.f32() * 2.0 - 1.0;
+        self.filtered_noise += voice.noise_cutoff * (white - self.filtered_noise);  // one-pole low pass
+        let attack = (self.index as f32 / (ATTACK_SECONDS * SAMPLE_RATE as f32)).min(1.0);
+        let envelope = attack * (1.0 - t).powf(voice.decay);
+        self.index += 1;
+        Some((tone * (1.0 - voice.noise) + self.filtered_noise * voice.noise) * envelope)
+    }
+}
+
+impl Source for SynthSource {
+    fn current_span_len(&self) -> Option<usize> {
+        Some(self.length - self.index)
+    }
+    fn channels(&self) -> ChannelCount {
+        1
+    }
+    fn sample_rate(&self) -> SampleRate {
+        SAMPLE_RATE
+    }
+    fn total_duration(&self) -> Option<Duration> {
+        Some(Duration::from_secs_f32(self.voice.seconds))
+    }
+}
@@ -0,0 +1,65 @@
+use std::time::Instant;
+use ::bevy::pbr::NotShadowCaster;
+use ::bevy::prelude::*;
+use ::jamscepticeye_2025::*;
+use ::jamscepticeye_2025::decoration::{DecorationChunkMesh, DecorationChunks, DecorationKind, Scatter};
+
+const FRAMES: u32 = 100;
+const CHUNKS: i32 = 4;  // scatters a CHUNKS x CHUNKS square of chunks
+
+// Headless decoration benchmark, run with `cargo run --bin decoration_bench`
+// (the dev profile is already optimized). Reports how many entities the
+// scatter used to cost (a parent and a mesh child per decoration) against
+// the merged chunk meshes drawn now.
+fn main() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+        .init_asset::<Mesh>()
+        .init_asset::<StandardMaterial>()
+        .add_plugins(decoration::DecorationPlugin);
+    let start = Instant::now();
+    app.world_mut().resource_scope(|world, mut chunks: Mut<DecorationChunks>| {
+        let scatter = world.resource::<Scatter>();
+        for x in 0..CHUNKS {
+            for y in 0..CHUNKS {
+                chunks.scatter_chunk(IVec2::new(x, y), scatter, None, &[]);
+            }
+        }
+    });
+    app.update();
+    let setup = start.elapsed();
+    let start = Instant::now();
+    for _ in 0..FRAMES {
+        app.update();
+    }
+    let frame = start.elapsed() / FRAMES;
+    let world = app.world_mut();
+    let decorations = world.resource::<DecorationChunks>().decoration_count();
+    let chunks = world.resource::<DecorationChunks>().chunks.len();
+    let chunk_meshes = world.query::<&DecorationChunkMesh>().iter(world).count();
+    let placements: Vec<(DecorationKind, Transform)> = world.resource::<DecorationChunks>().chunks
+        .values()
+        .flat_map(|chunk| chunk.placements.iter().copied())
+        .collect();
+    println!("decorations:           {decorations}");
+    println!("entities before:       {}", entities_before(&placements));
+    println!("entities after:        {chunk_meshes} ({chunks} chunks)");
+    println!("setup and first build: {setup:?}");
+    println!("steady frame:          {frame:?}");
+}
+
+// Spawns the same decorations the way the old scatter did, a parent and a
+// mesh child each, and counts the entities that takes.
+fn entities_before(placements: &[(DecorationKind, Transform)]) -> u32 {
+    let mut world = World::new();
+    let before = world.entities().len();
+    for &(_, transform) in placements {
+        world.spawn((transform, Visibility::default())).with_child((
+            Mesh3d(Handle::default()),
+            MeshMaterial3d::<StandardMaterial>(Handle::default()),
+            Transform::IDENTITY,
+            NotShadowCaster
+        ));
+    }
+    world.entities().len() - before
+}
@@ -0,0 +1,11 @@
+use ::bevy::prelude::*;
+use ::jamscepticeye_2025::*;
+
+// Prop inspection tool, run with `cargo run --bin previewer`.
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(assets::HomegrownAssetsPlugin)
+        .add_plugins(model_previewer::ModelViewerPlugin)
+        .run();
+}
@@ -3,6 +3,8 @@ use bevy::core_pipeline::bloom::Bloom;
 use bevy::core_pipeline::tonemapping::Tonemapping;
 use bevy::render::camera::ScalingMode;
 use bevy::transform::systems::propagate_parent_transforms;
+use serde::Deserialize;
+use crate::ground::GroundMap;
 
 pub const VIEW_WIDTH: f32 = 10.0;  // this controls the width of the screen (in world-units)
 const TRACKING: f32 = 4.0;  // MORE IS FASTER
@@ -13,12 +15,24 @@ pub struct CameraPos{
     pub vec2: Vec2
 }
 
+#[derive(Resource, Clone, Deserialize)]
+#[serde(default)]
+pub struct CameraTuning {
+    pub tracking: f32
+} impl Default for CameraTuning {
+    fn default() -> Self {
+        Self {
+            tracking: TRACKING
+        }
+    }
+}
+
 #[derive(Resource)]
 pub struct CameraLeaderEntity {
     pub entity: Entity
 }
 
-#[derive(Component, Default, Copy, Clone)]
+#[derive(Component, Copy, Clone)]
 #[require(CameraCommonComponent)]
 pub struct POVCameraFollower{
     pub weight: f32
@@ -28,7 +42,8 @@ pub struct POVCameraFollower{
             weight
         }
     }
-    pub fn default() -> Self {
+} impl Default for POVCameraFollower {
+    fn default() -> Self {
         Self::new(1.0)
     }
 }
@@ -36,6 +51,7 @@ pub struct POVCameraFollower{
 pub struct TopDownCameraPlugin;
 impl Plugin for TopDownCameraPlugin {
     fn build(&self, app: &mut App) {
+        app.init_resource::<CameraTuning>();
         app.add_systems(Startup, spawn_camera);
         app.add_systems(PostUpdate, move_camera.before(propagate_parent_transforms));
     }
@@ -92,6 +108,8 @@ fn move_camera(
     leader_query: Query<Entity, With<POVCameraLeader>>,
     follower_query: Query<(Entity, &POVCameraFollower)>,
     time: Res<Time>,
+    tuning: Res<CameraTuning>,
+    ground: Option<Res<GroundMap>>,
     mut camera_pos: ResMut<CameraPos>
 ) {
     let leader_entity = if let Ok(e) = leader_query.single() {
@@ -119,9 +137,12 @@ fn move_camera(
         return;
     };
 
-    let camera_goal = translation_sum / weight_sum;
+    let mut camera_goal = translation_sum / weight_sum;
+    if let Some(ground) = ground {
+        camera_goal.z = ground.ground_height(camera_goal.xy());  // look at the ground, not the hop
+    };
     let delta = camera_goal - leader_translation;
-    let new_translation = delta * (TRACKING * time.delta_secs()) + leader_translation;
+    let new_translation = delta * (tuning.tracking * time.delta_secs()) + leader_translation;
     if let Ok(mut t) = transform_query.get_mut(leader_entity) {
         if delta.abs().element_sum() < 1.0/128.0 {
             t.translation = camera_goal
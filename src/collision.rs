@@ -0,0 +1,158 @@
+use std::collections::{HashMap, HashSet};
+use bevy::prelude::*;
+use crate::ground;
+
+const CELL_SIZE: f32 = 2.0;  // must be at least the widest collider's diameter
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Body {
+    Static,  // never pushed, e.g. trees
+    Dynamic {mass: f32}  // pushed apart in proportion to the other body's mass
+}
+
+// Circle on the ground plane, its radius scaled with the entity's transform
+// so growing trees grow their collider too.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Collider {
+    pub radius: f32,
+    pub body: Body
+} impl Collider {
+    pub fn fixed(radius: f32) -> Self {
+        Self {
+            radius,
+            body: Body::Static
+        }
+    }
+    pub fn dynamic(radius: f32, mass: f32) -> Self {
+        Self {
+            radius,
+            body: Body::Dynamic {mass}
+        }
+    }
+    fn inverse_mass(&self) -> f32 {
+        match self.body {
+            Body::Static => 0.0,
+            Body::Dynamic {mass} => mass.max(f32::EPSILON).recip()
+        }
+    }
+}
+
+// Written every frame for every pair of overlapping colliders.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct CollisionEvent {
+    pub a: Entity,
+    pub b: Entity,
+    pub point: Vec2,  // on `b`'s edge, towards `a`
+    pub started: bool  // the pair wasn't touching last frame
+} impl CollisionEvent {
+    // The entity `entity` collided with, if it's part of this collision.
+    pub fn other(&self, entity: Entity) -> Option<Entity> {
+        if entity == self.a {
+            Some(self.b)
+        } else if entity == self.b {
+            Some(self.a)
+        } else {
+            None
+        }
+    }
+}
+
+// Pairs touching last frame, to tell new contacts from ongoing ones.
+#[derive(Resource, Default)]
+struct Contacts {
+    pairs: HashSet<(Entity, Entity)>
+}
+
+pub struct CollisionPlugin;
+impl Plugin for CollisionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Contacts>();
+        app.add_event::<CollisionEvent>();
+        app.add_systems(PostUpdate, resolve_collisions.before(ground::follow_ground));
+    }
+}
+
+fn cell_coord(position: Vec2) -> IVec2 {
+    (position / CELL_SIZE).floor().as_ivec2()
+}
+
+// Pushes overlapping colliders apart in a single pass, sharing the push by
+// inverse mass so static bodies never move. Deep pile-ups settle over a few
+// frames rather than being solved exactly. Pushes never shove a body off
+// walkable ground, only along whichever axis keeps it on.
+fn resolve_collisions(
+    ground: Option<Res<ground::GroundMap>>,
+    mut collider_query: Query<(Entity, &mut Transform, &Collider)>,
+    mut contacts: ResMut<Contacts>,
+    mut event_writer: EventWriter<CollisionEvent>
+) {
+    let bodies: Vec<(Entity, Vec2, f32, f32)> = collider_query
+        .iter()
+        .map(|(entity, transform, collider)| (entity, transform.translation.xy(), collider.radius * transform.scale.x, collider.inverse_mass()))
+        .collect();
+    let mut grid: HashMap<IVec2, Vec<usize>> = HashMap::new();
+    for (index, &(_, position, _, _)) in bodies.iter().enumerate() {
+        grid.entry(cell_coord(position)).or_default().push(index);
+    }
+    let mut pushes = vec![Vec2::ZERO; bodies.len()];
+    let mut touching = HashSet::new();
+    for (index, &(a, position, radius, inverse_mass)) in bodies.iter().enumerate() {
+        let cell = cell_coord(position);
+        for x in -1..=1 {
+            for y in -1..=1 {
+                let Some(others) = grid.get(&(cell + IVec2::new(x, y))) else {
+                    continue;
+                };
+                for &other in others.iter().filter(|&&other| other > index) {
+                    let (b, other_position, other_radius, other_inverse_mass) = bodies[other];
+                    let total_inverse_mass = inverse_mass + other_inverse_mass;
+                    if total_inverse_mass <= 0.0 {
+                        continue;  // two static bodies
+                    };
+                    let offset = position - other_position;
+                    let overlap = radius + other_radius - offset.length();
+                    if overlap <= 0.0 {
+                        continue;
+                    };
+                    let normal = offset.try_normalize().unwrap_or(Vec2::X);
+                    pushes[index] += normal * overlap * inverse_mass / total_inverse_mass;
+                    pushes[other] -= normal * overlap * other_inverse_mass / total_inverse_mass;
+                    let pair = (a.min(b), a.max(b));
+                    event_writer.write(CollisionEvent {
+                        a,
+                        b,
+                        point: other_position + normal * other_radius,
+                        started: !contacts.pairs.contains(&pair)
+                    });
+                    touching.insert(pair);
+                }
+            }
+        }
+    }
+    contacts.pairs = touching;
+    for (&(entity, position, _, _), push) in bodies.iter().zip(pushes) {
+        let push = match ground.as_deref() {
+            Some(ground) => walkable_push(ground, position, push),
+            None => push
+        };
+        if push == Vec2::ZERO {
+            continue;
+        };
+        if let Ok((_, mut transform, _)) = collider_query.get_mut(entity) {
+            transform.translation += push.extend(0.0);
+        };
+    }
+}
+
+// Clamps `push` to the part of it that keeps a body at `position` on
+// walkable ground. Bodies already off it, e.g. wading out of water, are
+// pushed freely so they can't get stuck.
+fn walkable_push(ground: &ground::GroundMap, position: Vec2, push: Vec2) -> Vec2 {
+    if !ground.is_walkable(position) {
+        return push;
+    };
+    [push, Vec2::new(push.x, 0.0), Vec2::new(0.0, push.y)]
+        .into_iter()
+        .find(|&push| ground.is_walkable(position + push))
+        .unwrap_or(Vec2::ZERO)
+}
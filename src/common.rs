@@ -2,7 +2,7 @@ use super::*;
 
 // === Random Spawn System ===
 // NOTE: Generic system that randomly spawns `T: Bundle` across
-//       a world with a predefined `w: f32` and `h: f32`.
+//       one chunk of the world, `world_w` by `world_h` from `origin`.
 
 pub trait RandomSpawnEntityConstructor {
     type Bundle: Bundle;
@@ -17,24 +17,46 @@ where
     pub world_h: f32,
     pub count: usize,
     pub min_spacing: f32,
-    pub max_attempt: usize
+    pub max_attempt: usize,
+    pub origin: Vec2,
+    pub seed: u64,
+    pub constructor: ::std::marker::PhantomData<T>
 }
 
 impl<T> RandomSpawnSystem<T>
 where
     T: RandomSpawnEntityConstructor {
-    pub fn on_startup(world: &mut World) {
+    // Spawns into one square chunk of the world, the same way every time
+    // for the same `seed`, only on open ground outside the player's spawn
+    // clearing.
+    pub fn on_chunk(world: &mut World, origin: Vec2, size: f32, seed: u64, count: usize, min_spacing: f32) -> Vec<Entity> {
         let model: Self = Self {
-            world_w: WORLD_W,
-            world_h: WORLD_H,
-            count: 200,
-            min_spacing: 20.0,
-            max_attempt: 256
+            world_w: size,
+            world_h: size,
+            count,
+            min_spacing,
+            max_attempt: count * 8,
+            origin,
+            seed,
+            constructor: ::std::marker::PhantomData
         };
-        for position in model.simulate() {
-            let position: Vec3 = position.into();
-            world.spawn(T::new(world, position));
-        }
+        let positions: Vec<Vec2> = model.simulate();
+        let positions: Vec<Vec2> = match world.get_resource::<ground::GroundMap>() {
+            Some(ground) => positions.into_iter().filter(|&position| ground.is_fertile(position)).collect(),
+            None => positions
+        };
+        let spawn: Vec2 = player_controls::PLAYER_DEFAULT_POS.truncate();
+        let positions: Vec<Vec2> = positions
+            .into_iter()
+            .filter(|position| position.distance(spawn) >= ground::SPAWN_CLEAR_RADIUS)
+            .collect();
+        positions
+            .into_iter()
+            .map(|position| {
+                let position: Vec3 = position.extend(0.0);
+                world.spawn(T::new(world, position)).id()
+            })
+            .collect()
     }
 }
 
@@ -44,10 +66,14 @@ where
     type Output = Vec<Vec2>;
     
     fn simulate(self) -> Self::Output {
+        let mut rng: ::fastrand::Rng = ::fastrand::Rng::with_seed(self.seed);
         let mut positions: Vec<Vec2> = Vec::new();
         for _ in 0..self.max_attempt {
-            let x: f32 = ::fastrand::f32() * self.world_w;
-            let y: f32 = ::fastrand::f32() * self.world_h;
+            if positions.len() >= self.count {
+                break
+            }
+            let x: f32 = self.origin.x + rng.f32() * self.world_w;
+            let y: f32 = self.origin.y + rng.f32() * self.world_h;
             let next: Vec2 = (x, y).into();
             if positions.iter().any(|position| {
                 position.distance(next) < self.min_spacing
@@ -58,4 +84,43 @@ where
         }
         positions
     }
-}
\ No newline at end of file
+}
+
+// === Spatial Index ===
+// NOTE: Buckets items into square cells so neighbour lookups only visit
+//       the cells around the query rather than every item.
+
+pub struct SpatialIndex<T> {
+    pub cell_size: f32,
+    cells: ::std::collections::HashMap<IVec2, Vec<(Vec2, T)>>
+}
+
+impl<T> SpatialIndex<T> {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: ::std::collections::HashMap::new()
+        }
+    }
+
+    fn cell(&self, position: Vec2) -> IVec2 {
+        (position / self.cell_size).floor().as_ivec2()
+    }
+
+    pub fn insert(&mut self, position: Vec2, item: T) {
+        let cell: IVec2 = self.cell(position);
+        self.cells.entry(cell).or_default().push((position, item));
+    }
+
+    // Every item within `radius` of `position`.
+    pub fn query(&self, position: Vec2, radius: f32) -> impl Iterator<Item = (Vec2, &T)> {
+        let min: IVec2 = self.cell(position - radius);
+        let max: IVec2 = self.cell(position + radius);
+        (min.x..=max.x)
+            .flat_map(move |x| (min.y..=max.y).map(move |y| IVec2::new(x, y)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .filter(move |(other, _)| other.distance_squared(position) <= radius * radius)
+            .map(|(other, item)| (*other, item))
+    }
+}
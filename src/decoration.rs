@@ -0,0 +1,268 @@
+use std::collections::HashMap;
+use std::f32::consts::{FRAC_PI_2, TAU};
+use bevy::pbr::NotShadowCaster;
+use bevy::prelude::*;
+use fastrand::Rng;
+use serde::{Deserialize, Serialize};
+use crate::ground::{self, GroundMap};
+use crate::player_controls::PLAYER_DEFAULT_POS;
+use crate::tree::{GrowEvent, Tree};
+
+const SEED: u64 = 0x5eed;
+const PLAYER_EXCLUSION_RADIUS: f32 = 2.0;  // keeps the spawn point clear
+pub const CHUNK_SIZE: f32 = ground::CHUNK_SIZE;  // decorations stream with the ground chunks
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Serialize, Deserialize)]
+pub enum DecorationKind {
+    Stone,
+    Grass,
+    Flower,
+    Mushroom
+} impl DecorationKind {
+    pub const ALL: [Self; 4] = [Self::Stone, Self::Grass, Self::Flower, Self::Mushroom];
+}
+
+// How one kind of decoration is scattered.
+#[derive(Clone)]
+pub struct DecorationType {
+    pub kind: DecorationKind,
+    pub density: f32,  // decorations per square world unit
+    pub min_scale: f32,
+    pub max_scale: f32,
+    pub rotation_jitter: f32,  // radians either side of the default facing
+    pub exclusion_radius: f32  // kept this far from trees
+}
+
+#[derive(Resource, Clone)]
+pub struct Scatter {
+    pub seed: u64,
+    pub player_exclusion_radius: f32,
+    pub types: Vec<DecorationType>
+} impl Default for Scatter {
+    fn default() -> Self {
+        Self {
+            seed: SEED,
+            player_exclusion_radius: PLAYER_EXCLUSION_RADIUS,
+            types: vec![
+                DecorationType {kind: DecorationKind::Stone, density: 0.3, min_scale: 0.5, max_scale: 1.5, rotation_jitter: TAU, exclusion_radius: 0.5},
+                DecorationType {kind: DecorationKind::Grass, density: 1.0, min_scale: 0.6, max_scale: 1.4, rotation_jitter: 0.3, exclusion_radius: 0.3},
+                DecorationType {kind: DecorationKind::Flower, density: 0.3, min_scale: 0.8, max_scale: 1.2, rotation_jitter: TAU, exclusion_radius: 0.6},
+                DecorationType {kind: DecorationKind::Mushroom, density: 0.1, min_scale: 0.7, max_scale: 1.3, rotation_jitter: TAU, exclusion_radius: 0.0}
+            ]
+        }
+    }
+} impl Scatter {
+    pub fn chunk_seed(&self, coord: IVec2) -> u64 {
+        self.seed ^ ((coord.x as u32 as u64) << 32) ^ coord.y as u32 as u64
+    }
+    // Places every decoration type over `bounds` on a jittered grid. The
+    // same seed, bounds and exclusions always give the same placements.
+    pub fn placements(&self, bounds: Rect, seed: u64, trees: &[Vec2], player_spawn: Vec2) -> Vec<(DecorationKind, Transform)> {
+        let mut placements = Vec::new();
+        for (index, decoration) in self.types.iter().enumerate() {
+            if decoration.density <= 0.0 {
+                continue;
+            };
+            let mut rng = Rng::with_seed(seed.wrapping_add(index as u64));
+            let spacing = decoration.density.recip().sqrt();
+            let columns = (bounds.width() / spacing).ceil() as usize;
+            let rows = (bounds.height() / spacing).ceil() as usize;
+            for column in 0..columns {
+                for row in 0..rows {
+                    let jitter = Vec2::new(rng.f32(), rng.f32());
+                    let position = bounds.min + (Vec2::new(column as f32, row as f32) + jitter) * spacing;
+                    let scale = decoration.min_scale + rng.f32() * (decoration.max_scale - decoration.min_scale);
+                    let angle = (rng.f32() - 0.5) * 2.0 * decoration.rotation_jitter;
+                    if !bounds.contains(position)
+                        || position.distance(player_spawn) < self.player_exclusion_radius
+                        || trees.iter().any(|tree| tree.distance(position) < decoration.exclusion_radius)
+                    {
+                        continue;
+                    };
+                    let transform = Transform::from_translation(position.extend(0.0))
+                        .with_rotation(Quat::from_rotation_z(angle))
+                        .with_scale(Vec3::splat(scale));
+                    placements.push((decoration.kind, transform));
+                }
+            }
+        }
+        placements
+    }
+}
+
+// Decorations are plain data grouped by chunk. Each chunk is drawn as one
+// merged mesh per kind, so the entity count scales with the number of
+// chunks rather than the number of decorations.
+#[derive(Default)]
+pub struct DecorationChunk {
+    pub placements: Vec<(DecorationKind, Transform)>,
+    dirty: bool,
+    entities: Vec<Entity>
+}
+
+#[derive(Resource, Default)]
+pub struct DecorationChunks {
+    pub chunks: HashMap<IVec2, DecorationChunk>
+} impl DecorationChunks {
+    pub fn chunk_coord(position: Vec2) -> IVec2 {
+        (position / CHUNK_SIZE).floor().as_ivec2()
+    }
+    pub fn insert(&mut self, kind: DecorationKind, transform: Transform) {
+        let chunk = self.chunks.entry(Self::chunk_coord(transform.translation.xy())).or_default();
+        chunk.placements.push((kind, transform));
+        chunk.dirty = true;
+    }
+    // Removes decorations within `radius` of `position`, only marking the
+    // chunks that actually lost something for a rebuild.
+    pub fn clear_around(&mut self, position: Vec2, radius: f32) {
+        let min = Self::chunk_coord(position - radius);
+        let max = Self::chunk_coord(position + radius);
+        for x in min.x..=max.x {
+            for y in min.y..=max.y {
+                let Some(chunk) = self.chunks.get_mut(&IVec2::new(x, y)) else {
+                    continue;
+                };
+                let count = chunk.placements.len();
+                chunk.placements.retain(|(_, t)| t.translation.xy().distance(position) >= radius);
+                chunk.dirty |= chunk.placements.len() != count;
+            }
+        }
+    }
+    // Scatters a freshly loaded chunk, keeping off unwalkable ground.
+    pub fn scatter_chunk(&mut self, coord: IVec2, scatter: &Scatter, ground: Option<&GroundMap>, trees: &[Vec2]) {
+        let chunk = self.chunks.entry(coord).or_default();
+        chunk.dirty = true;
+        let bounds = Rect::from_corners(coord.as_vec2() * CHUNK_SIZE, (coord + 1).as_vec2() * CHUNK_SIZE);
+        for (kind, mut transform) in scatter.placements(bounds, scatter.chunk_seed(coord), trees, PLAYER_DEFAULT_POS.xy()) {
+            let position = transform.translation.xy();
+            if let Some(ground) = ground {
+                if !ground.is_fertile(position) {
+                    continue;  // nothing grows in water or on bare rock
+                };
+                transform.translation.z = ground.ground_height(position);
+            };
+            chunk.placements.push((kind, transform));
+        }
+    }
+    // Loads a chunk with placements saved before it was unloaded, in place
+    // of scattering it afresh.
+    pub fn restore_chunk(&mut self, coord: IVec2, placements: Vec<(DecorationKind, Transform)>) {
+        let chunk = self.chunks.entry(coord).or_default();
+        chunk.placements = placements;
+        chunk.dirty = true;
+    }
+    // Drops the chunk, returning its mesh entities for despawning.
+    pub fn unload_chunk(&mut self, coord: IVec2) -> Vec<Entity> {
+        self.chunks.remove(&coord).map_or(Vec::new(), |chunk| chunk.entities)
+    }
+    pub fn decoration_count(&self) -> usize {
+        self.chunks.values().map(|chunk| chunk.placements.len()).sum()
+    }
+}
+
+#[derive(Component)]
+pub struct DecorationChunkMesh {
+    pub coord: IVec2,
+    pub kind: DecorationKind
+}
+
+// Low-poly template mesh and material for each kind, baked into chunk meshes.
+#[derive(Resource)]
+struct DecorationAssets {
+    templates: HashMap<DecorationKind, (Mesh, Handle<StandardMaterial>)>
+}
+
+pub struct DecorationPlugin;
+impl Plugin for DecorationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Scatter>();
+        app.init_resource::<DecorationChunks>();
+        app.add_event::<GrowEvent>();  // also registered by `tree::Plugin`, so decorations work without it
+        app.add_systems(PreStartup, create_decoration_assets);
+        app.add_systems(Update, (clear_under_trees, rebuild_dirty_chunks).chain());
+    }
+}
+
+// Meshes are built along +y, the world is z-up.
+fn upright(offset: f32) -> Transform {
+    Transform::from_translation(Vec3::Z * offset).with_rotation(Quat::from_rotation_x(FRAC_PI_2))
+}
+
+fn create_decoration_assets(
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut commands: Commands
+) {
+    let mut material = |r: f32, g: f32, b: f32| materials.add(StandardMaterial {
+        base_color: Color::linear_rgb(r, g, b),
+        perceptual_roughness: 0.9,
+        ..default()
+    });
+    let templates = HashMap::from([
+        (DecorationKind::Stone, (Sphere::new(0.125).mesh().uv(8, 6), material(0.3, 0.3, 0.3))),
+        (DecorationKind::Grass, (Cone::new(0.05, 0.25).mesh().resolution(4).build().transformed_by(upright(0.125)), material(0.2, 0.6, 0.1))),
+        (DecorationKind::Flower, (Sphere::new(0.06).mesh().uv(6, 4).transformed_by(Transform::from_xyz(0.0, 0.0, 0.12)), material(0.9, 0.8, 0.2))),
+        (DecorationKind::Mushroom, (Cone::new(0.1, 0.08).mesh().resolution(8).build().transformed_by(upright(0.08)), material(0.8, 0.15, 0.1)))
+    ]);
+    commands.insert_resource(DecorationAssets {templates});
+}
+
+// Growing trees push out the decorations underneath them.
+fn clear_under_trees(
+    mut event_reader: EventReader<GrowEvent>,
+    tree_query: Query<&Transform, With<Tree>>,
+    mut chunks: ResMut<DecorationChunks>
+) {
+    for event in event_reader.read() {
+        if let Ok(transform) = tree_query.get(event.entity) {
+            chunks.clear_around(transform.translation.xy(), event.stage.radius() * 2.0);
+        };
+    }
+}
+
+fn merge_placements<'a>(template: &Mesh, transforms: impl Iterator<Item = &'a Transform>) -> Option<Mesh> {
+    let mut merged: Option<Mesh> = None;
+    for &transform in transforms {
+        let mesh = template.clone().transformed_by(transform);
+        match &mut merged {
+            Some(merged) => {
+                if let Err(error) = merged.merge(&mesh) {
+                    warn!("could not merge decoration mesh: {error}");
+                };
+            },
+            None => merged = Some(mesh)
+        }
+    }
+    merged
+}
+
+fn rebuild_dirty_chunks(
+    decoration_assets: Option<Res<DecorationAssets>>,
+    mut chunks: ResMut<DecorationChunks>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut commands: Commands
+) {
+    let Some(decoration_assets) = decoration_assets else {
+        return;
+    };
+    for (&coord, chunk) in chunks.chunks.iter_mut().filter(|(_, chunk)| chunk.dirty) {
+        for entity in chunk.entities.drain(..) {
+            commands.entity(entity).try_despawn();
+        }
+        for kind in DecorationKind::ALL {
+            let (template, material) = &decoration_assets.templates[&kind];
+            let transforms = chunk.placements.iter().filter(|(k, _)| *k == kind).map(|(_, t)| t);
+            let Some(mesh) = merge_placements(template, transforms) else {
+                continue;
+            };
+            let entity = commands.spawn((
+                Mesh3d(meshes.add(mesh)),
+                MeshMaterial3d(material.clone()),
+                Transform::IDENTITY,
+                NotShadowCaster,
+                DecorationChunkMesh {coord, kind}
+            )).id();
+            chunk.entities.push(entity);
+        }
+        chunk.dirty = false;
+    }
+}
@@ -1,31 +1,144 @@
+use std::f32::consts::TAU;
 use ::bevy::prelude::*;
+use serde::Deserialize;
+
+mod weather;
+pub use weather::{Weather, WeatherParams, WeatherState, Wind};
 
 const LIGHT_INTENSITY: f32 = 10_000.0;
 const AMBIENT_INTENSITY: f32 = 100.0;  // darken this value for darker shadows
+const NIGHT_AMBIENT_INTENSITY: f32 = 20.0;
 const LIGHT_TRANSLATION: Vec3 = Vec3::new(1.0, 1.0, 2.0);  // light looks at 0,0,0 so this controls its angle easily
+const DAY_LENGTH_SECONDS: f32 = 240.0;
+const START_TIME_OF_DAY: f32 = 0.3;  // 0.0 is midnight, 0.25 sunrise, 0.5 noon, 0.75 sunset
+const TWILIGHT_ELEVATION: f32 = 0.2;  // sun elevation (sine) below which it is dawn/dusk
+const NIGHT_ELEVATION: f32 = -0.1;  // and below which it is night
+const NOON_COLOR: Vec3 = Vec3::new(1.0, 1.0, 1.0);
+const HORIZON_COLOR: Vec3 = Vec3::new(1.0, 0.6, 0.3);
+const NIGHT_COLOR: Vec3 = Vec3::new(0.3, 0.4, 0.8);
+
+#[derive(Resource, Clone, Deserialize)]
+#[serde(default)]
+pub struct EnvironmentTuning {
+    pub light_intensity: f32,
+    pub ambient_intensity: f32,
+    pub night_ambient_intensity: f32,
+    pub day_length_seconds: f32
+} impl Default for EnvironmentTuning {
+    fn default() -> Self {
+        Self {
+            light_intensity: LIGHT_INTENSITY,
+            ambient_intensity: AMBIENT_INTENSITY,
+            night_ambient_intensity: NIGHT_AMBIENT_INTENSITY,
+            day_length_seconds: DAY_LENGTH_SECONDS
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum DayPhase {
+    Dawn,
+    Day,
+    Dusk,
+    Night
+}
+
+#[derive(Resource)]
+pub struct TimeOfDay {
+    pub fraction: f32  // 0.0..1.0 through the day, 0.0 is midnight
+} impl TimeOfDay {
+    // Sine of the sun's angle above the horizon, 1.0 at noon.
+    pub fn sun_elevation(&self) -> f32 {
+        ((self.fraction - 0.25) * TAU).sin()
+    }
+    pub fn phase(&self) -> DayPhase {
+        let elevation = self.sun_elevation();
+        if elevation < NIGHT_ELEVATION {
+            DayPhase::Night
+        } else if elevation >= TWILIGHT_ELEVATION {
+            DayPhase::Day
+        } else if self.fraction < 0.5 {
+            DayPhase::Dawn
+        } else {
+            DayPhase::Dusk
+        }
+    }
+    // 0.0 at night rising to 1.0 once the sun is clear of the horizon.
+    pub fn daylight(&self) -> f32 {
+        let t = ((self.sun_elevation() - NIGHT_ELEVATION) / (TWILIGHT_ELEVATION - NIGHT_ELEVATION)).clamp(0.0, 1.0);
+        t * t * (3.0 - 2.0 * t)
+    }
+}
+
+#[derive(Component)]
+pub struct Sun;
 
 pub struct EnvironmentPlugin;
 impl Plugin for EnvironmentPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, spawn_lights);
+        app.init_resource::<EnvironmentTuning>();
+        app.insert_resource(TimeOfDay {fraction: START_TIME_OF_DAY});
+        app.insert_resource(WeatherState::new(Weather::Clear));
+        app.init_resource::<Wind>();
+        app.add_systems(Startup, (spawn_lights, weather::spawn_rain));
+        app.add_systems(Update, (
+            (advance_time_of_day, apply_time_of_day, weather::advance_weather, weather::apply_weather).chain(),
+            (weather::update_wind, weather::simulate_rain).chain().after(weather::advance_weather)
+        ));
     }
 }
 
 fn spawn_lights(
-    mut commands: Commands
+    mut commands: Commands,
+    tuning: Res<EnvironmentTuning>
 ) {
     commands.spawn((
         DirectionalLight {
             color: Color::WHITE,
-            illuminance: LIGHT_INTENSITY,
+            illuminance: tuning.light_intensity,
             shadows_enabled: true,
             ..default()
         },
-        Transform::from_translation(LIGHT_TRANSLATION).looking_at(Vec3::ZERO, Vec3::Z)
+        Transform::from_translation(LIGHT_TRANSLATION).looking_at(Vec3::ZERO, Vec3::Z),
+        Sun
     ));
     commands.insert_resource(AmbientLight{
         color: Color::WHITE,
-        brightness: AMBIENT_INTENSITY,
+        brightness: tuning.ambient_intensity,
         ..default()
     });
-}
\ No newline at end of file
+}
+
+fn advance_time_of_day(
+    time: Res<Time>,
+    tuning: Res<EnvironmentTuning>,
+    mut time_of_day: ResMut<TimeOfDay>
+) {
+    let step = time.delta_secs() / tuning.day_length_seconds.max(f32::EPSILON);
+    time_of_day.fraction = (time_of_day.fraction + step).fract();
+}
+
+// Swings the sun east to west across the sky, keeping the tilt of
+// `LIGHT_TRANSLATION`, and fades light and ambient through the day.
+fn apply_time_of_day(
+    time_of_day: Res<TimeOfDay>,
+    tuning: Res<EnvironmentTuning>,
+    sun_query: Query<(&mut DirectionalLight, &mut Transform), With<Sun>>,
+    mut ambient: ResMut<AmbientLight>
+) {
+    let angle = (time_of_day.fraction - 0.25) * TAU;
+    let radius = LIGHT_TRANSLATION.xz().length();
+    let translation = Vec3::new(angle.cos() * radius, LIGHT_TRANSLATION.y, angle.sin() * radius);
+    let daylight = time_of_day.daylight();
+    let elevation = time_of_day.sun_elevation().max(0.0);
+    let day_color = HORIZON_COLOR.lerp(NOON_COLOR, elevation);
+    let color = NIGHT_COLOR.lerp(day_color, daylight);
+    for (mut light, mut transform) in sun_query {
+        *transform = Transform::from_translation(translation).looking_at(Vec3::ZERO, Vec3::Z);
+        light.illuminance = tuning.light_intensity * daylight;
+        light.color = Color::linear_rgb(color.x, color.y, color.z);
+    }
+    ambient.brightness = tuning.night_ambient_intensity
+        + (tuning.ambient_intensity - tuning.night_ambient_intensity) * daylight;
+    ambient.color = Color::linear_rgb(color.x, color.y, color.z);
+}
@@ -0,0 +1,220 @@
+use std::f32::consts::TAU;
+use bevy::pbr::{DistanceFog, FogFalloff, NotShadowCaster};
+use bevy::prelude::*;
+use crate::camera_module::{CameraPos, VIEW_WIDTH};
+use crate::environment::Sun;
+
+const MIN_WEATHER_SECONDS: f32 = 60.0;
+const MAX_WEATHER_SECONDS: f32 = 180.0;
+const TRANSITION_SECONDS: f32 = 10.0;
+const FOG_COLOR: Color = Color::linear_rgba(0.6, 0.62, 0.65, 1.0);
+
+const RAIN_DROPS: usize = 600;
+const RAIN_RADIUS: f32 = VIEW_WIDTH;  // drops fall in a square this far either side of the camera
+const RAIN_HEIGHT: f32 = 8.0;
+const RAIN_FALL_SPEED: f32 = 12.0;
+const RAIN_WIND_DRIFT: f32 = 1.5;  // how strongly wind slants the rain
+const RAIN_DROP_SIZE: Vec3 = Vec3::new(0.01, 0.01, 0.3);
+
+const WIND_TURN_SPEED: f32 = 0.05;  // radians per second the wind direction wanders
+const GUST_SPEED: f32 = 0.7;
+const GUST_STRENGTH: f32 = 0.3;  // fraction of the wind strength added or removed by gusts
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Weather {
+    Clear,
+    Overcast,
+    Rain,
+    Fog,
+    Windy
+} impl Weather {
+    // Which weathers this one may turn into.
+    fn transitions(&self) -> &'static [Self] {
+        match self {
+            Self::Clear => &[Self::Overcast, Self::Fog, Self::Windy],
+            Self::Overcast => &[Self::Clear, Self::Rain, Self::Windy],
+            Self::Rain => &[Self::Overcast],
+            Self::Fog => &[Self::Clear, Self::Overcast],
+            Self::Windy => &[Self::Clear, Self::Overcast]
+        }
+    }
+    fn params(&self) -> WeatherParams {
+        match self {
+            Self::Clear => WeatherParams {fog_visibility: 500.0, light: 1.0, rain: 0.0, wind: 0.2},
+            Self::Overcast => WeatherParams {fog_visibility: 150.0, light: 0.6, rain: 0.0, wind: 0.5},
+            Self::Rain => WeatherParams {fog_visibility: 80.0, light: 0.4, rain: 1.0, wind: 0.8},
+            Self::Fog => WeatherParams {fog_visibility: 30.0, light: 0.7, rain: 0.0, wind: 0.1},
+            Self::Windy => WeatherParams {fog_visibility: 400.0, light: 0.9, rain: 0.0, wind: 2.0}
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct WeatherParams {
+    pub fog_visibility: f32,
+    pub light: f32,  // multiplier on sun and ambient light
+    pub rain: f32,  // 0.0..1.0 fraction of rain drops falling
+    pub wind: f32  // wind strength in world units per second
+} impl WeatherParams {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        Self {
+            fog_visibility: self.fog_visibility + (other.fog_visibility - self.fog_visibility) * t,
+            light: self.light + (other.light - self.light) * t,
+            rain: self.rain + (other.rain - self.rain) * t,
+            wind: self.wind + (other.wind - self.wind) * t
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct WeatherState {
+    pub current: Weather,
+    pub next: Weather,  // equal to `current` unless a transition is running
+    pub transition: f32,  // 0.0..1.0 progress from `current` to `next`
+    remaining: f32  // seconds until the next transition starts
+} impl WeatherState {
+    pub fn new(weather: Weather) -> Self {
+        Self {
+            current: weather,
+            next: weather,
+            transition: 0.0,
+            remaining: random_weather_seconds()
+        }
+    }
+    pub fn params(&self) -> WeatherParams {
+        self.current.params().lerp(self.next.params(), self.transition)
+    }
+}
+
+fn random_weather_seconds() -> f32 {
+    MIN_WEATHER_SECONDS + fastrand::f32() * (MAX_WEATHER_SECONDS - MIN_WEATHER_SECONDS)
+}
+
+#[derive(Resource, Default)]
+pub struct Wind {
+    pub vector: Vec2,  // world units per second
+    angle: f32,
+    time: f32
+}
+
+#[derive(Component)]
+pub(super) struct RainDrop {
+    index: usize
+}
+
+pub(super) fn spawn_rain(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>
+) {
+    let mesh = meshes.add(Cuboid::from_size(RAIN_DROP_SIZE));
+    let material = materials.add(StandardMaterial {
+        base_color: Color::linear_rgba(0.6, 0.7, 0.9, 0.5),
+        alpha_mode: AlphaMode::Blend,
+        unlit: true,
+        ..default()
+    });
+    for index in 0..RAIN_DROPS {
+        commands.spawn((
+            Mesh3d(mesh.clone()),
+            MeshMaterial3d(material.clone()),
+            Transform::from_translation(random_drop_position(Vec2::ZERO, RAIN_HEIGHT * fastrand::f32())),
+            Visibility::Hidden,
+            NotShadowCaster,
+            RainDrop {index}
+        ));
+    }
+}
+
+fn random_drop_position(center: Vec2, z: f32) -> Vec3 {
+    let offset = Vec2::new(fastrand::f32() - 0.5, fastrand::f32() - 0.5) * 2.0 * RAIN_RADIUS;
+    (center + offset).extend(z)
+}
+
+pub(super) fn advance_weather(
+    time: Res<Time>,
+    mut weather: ResMut<WeatherState>
+) {
+    let dt = time.delta_secs();
+    if weather.current != weather.next {
+        weather.transition += dt / TRANSITION_SECONDS;
+        if weather.transition >= 1.0 {
+            weather.current = weather.next;
+            weather.transition = 0.0;
+            weather.remaining = random_weather_seconds();
+        };
+        return;
+    };
+    weather.remaining -= dt;
+    if weather.remaining <= 0.0 {
+        let options = weather.current.transitions();
+        weather.next = options[fastrand::usize(..options.len())];
+    };
+}
+
+pub(super) fn update_wind(
+    time: Res<Time>,
+    weather: Res<WeatherState>,
+    mut wind: ResMut<Wind>
+) {
+    let dt = time.delta_secs();
+    wind.time += dt;
+    wind.angle = (wind.angle + (fastrand::f32() - 0.5) * 2.0 * WIND_TURN_SPEED * dt) % TAU;
+    let gust = 1.0 + (wind.time * GUST_SPEED * TAU).sin() * GUST_STRENGTH;
+    wind.vector = Vec2::from_angle(wind.angle) * weather.params().wind * gust;
+}
+
+// Runs after the day/night cycle has set the lights for this frame.
+pub(super) fn apply_weather(
+    weather: Res<WeatherState>,
+    camera_query: Query<(Entity, Option<&mut DistanceFog>), With<Camera3d>>,
+    sun_query: Query<&mut DirectionalLight, With<Sun>>,
+    mut ambient: ResMut<AmbientLight>,
+    mut commands: Commands
+) {
+    let params = weather.params();
+    let falloff = FogFalloff::from_visibility(params.fog_visibility);
+    for (entity, fog) in camera_query {
+        match fog {
+            Some(mut fog) => fog.falloff = falloff.clone(),
+            None => {
+                commands.entity(entity).insert(DistanceFog {
+                    color: FOG_COLOR,
+                    falloff: falloff.clone(),
+                    ..default()
+                });
+            }
+        }
+    }
+    for mut light in sun_query {
+        light.illuminance *= params.light;
+    }
+    ambient.brightness *= params.light;
+}
+
+pub(super) fn simulate_rain(
+    time: Res<Time>,
+    weather: Res<WeatherState>,
+    wind: Res<Wind>,
+    camera_pos: Option<Res<CameraPos>>,
+    drop_query: Query<(&mut Transform, &mut Visibility, &RainDrop)>
+) {
+    let dt = time.delta_secs();
+    let center = camera_pos.map_or(Vec2::ZERO, |c| c.vec2);
+    let falling = (weather.params().rain * RAIN_DROPS as f32) as usize;
+    let velocity = (wind.vector * RAIN_WIND_DRIFT).extend(-RAIN_FALL_SPEED);
+    let rotation = Quat::from_rotation_arc(Vec3::NEG_Z, velocity.normalize());
+    for (mut transform, mut visibility, drop) in drop_query {
+        if drop.index >= falling {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+        *visibility = Visibility::Inherited;
+        transform.translation += velocity * dt;
+        transform.rotation = rotation;
+        let offset = transform.translation.xy() - center;
+        if transform.translation.z < 0.0 || offset.abs().max_element() > RAIN_RADIUS {
+            transform.translation = random_drop_position(center, RAIN_HEIGHT);
+        };
+    }
+}
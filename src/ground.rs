@@ -0,0 +1,353 @@
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use bevy::asset::RenderAssetUsages;
+use bevy::pbr::NotShadowCaster;
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::transform::TransformSystem;
+use serde::{Deserialize, Serialize};
+use crate::noise;
+use crate::player_controls::PLAYER_DEFAULT_POS;
+
+const SEED: u64 = 0x6e0d;
+pub const TILE_SIZE: f32 = 1.0;
+pub const CHUNK_TILES: i32 = 16;  // tiles along each side of a chunk
+pub const CHUNK_SIZE: f32 = CHUNK_TILES as f32 * TILE_SIZE;
+pub const GROUND_Z: f32 = 0.0;
+const ELEVATION_SCALE: f32 = 0.06;  // lower gives broader lakes and ridges
+const MOISTURE_SCALE: f32 = 0.1;
+const WATER_LEVEL: f32 = 0.32;
+const ROCK_LEVEL: f32 = 0.7;
+const DRY_LEVEL: f32 = 0.42;  // moisture below which grass gives way to dirt
+pub const SPAWN_CLEAR_RADIUS: f32 = 3.0;  // tiles around the player spawn are always grass
+const RELIEF: f32 = 6.0;  // height gained per unit of elevation above the water level
+const RIVER_SCALE: f32 = 0.02;  // lower gives longer, lazier rivers
+const RIVER_WIDTH: f32 = 0.015;  // in noise units either side of the river's centre line
+const SHALLOWS_WIDTH: f32 = 0.03;  // the wadeable bank beyond that
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Serialize, Deserialize)]
+pub enum Tile {
+    Grass,
+    Dirt,
+    Shallows,
+    Water,
+    Rock
+} impl Tile {
+    pub const ALL: [Self; 5] = [Self::Grass, Self::Dirt, Self::Shallows, Self::Water, Self::Rock];
+
+    pub fn walkable(&self) -> bool {
+        self.speed_factor() > 0.0
+    }
+    // Fraction of normal speed kept while crossing this tile.
+    pub fn speed_factor(&self) -> f32 {
+        match self {
+            Self::Grass | Self::Dirt => 1.0,
+            Self::Shallows => 0.5,
+            Self::Water | Self::Rock => 0.0
+        }
+    }
+    // Open ground that plants grow on and animals are born on.
+    pub fn fertile(&self) -> bool {
+        matches!(self, Self::Grass | Self::Dirt)
+    }
+    fn material(&self) -> StandardMaterial {
+        let (color, roughness) = match self {
+            Self::Grass => (Color::linear_rgb(0.12, 0.35, 0.08), 0.9),
+            Self::Dirt => (Color::linear_rgb(0.3, 0.2, 0.1), 1.0),
+            Self::Shallows => (Color::linear_rgb(0.1, 0.3, 0.45), 0.2),
+            Self::Water => (Color::linear_rgb(0.05, 0.15, 0.4), 0.1),
+            Self::Rock => (Color::linear_rgb(0.35, 0.35, 0.33), 0.8)
+        };
+        StandardMaterial {
+            base_color: color,
+            perceptual_roughness: roughness,
+            ..default()
+        }
+    }
+}
+
+pub struct GroundChunk {
+    tiles: Vec<Tile>,  // row-major, `CHUNK_TILES` squared
+    dirty: bool,
+    edited: bool,  // a tile was set since it was generated
+    entities: Vec<Entity>
+} impl GroundChunk {
+    fn generate(seed: u64, coord: IVec2) -> Self {
+        let mut tiles = Vec::with_capacity((CHUNK_TILES * CHUNK_TILES) as usize);
+        for y in 0..CHUNK_TILES {
+            for x in 0..CHUNK_TILES {
+                tiles.push(generate_tile(seed, coord * CHUNK_TILES + IVec2::new(x, y)));
+            }
+        }
+        Self {
+            tiles,
+            dirty: true,
+            edited: false,
+            entities: Vec::new()
+        }
+    }
+    fn index(local: IVec2) -> usize {
+        (local.y * CHUNK_TILES + local.x) as usize
+    }
+}
+
+fn elevation(seed: u64, position: Vec2) -> f32 {
+    noise::fbm(seed, position * ELEVATION_SCALE, 4)
+}
+
+// How far `position` is from the centre line of the nearest river, in noise
+// units. Rivers follow a contour of their own noise field so they meander
+// and never end abruptly.
+fn river_distance(seed: u64, position: Vec2) -> f32 {
+    (noise::fbm(seed.wrapping_add(0x200), position * RIVER_SCALE, 3) - 0.5).abs()
+}
+
+// Terrain height, flat at the water level and rising with elevation towards
+// the rocky ridges, with river beds carved back down to the water level.
+// Continuous everywhere, generated or not.
+pub fn ground_height(seed: u64, position: Vec2) -> f32 {
+    let t = ((river_distance(seed, position) - RIVER_WIDTH) / (SHALLOWS_WIDTH * 2.0 - RIVER_WIDTH)).clamp(0.0, 1.0);
+    let carve = t * t * (3.0 - 2.0 * t);
+    GROUND_Z + (elevation(seed, position) - WATER_LEVEL).max(0.0) * RELIEF * carve
+}
+
+fn ground_normal(seed: u64, position: Vec2) -> Vec3 {
+    let step = TILE_SIZE * 0.25;
+    let dx = ground_height(seed, position + Vec2::X * step) - ground_height(seed, position - Vec2::X * step);
+    let dy = ground_height(seed, position + Vec2::Y * step) - ground_height(seed, position - Vec2::Y * step);
+    Vec3::new(-dx, -dy, 2.0 * step).normalize()
+}
+
+fn generate_tile(seed: u64, tile: IVec2) -> Tile {
+    let center = (tile.as_vec2() + 0.5) * TILE_SIZE;
+    if center.distance(PLAYER_DEFAULT_POS.xy()) < SPAWN_CLEAR_RADIUS {
+        return Tile::Grass;
+    };
+    let elevation = elevation(seed, center);
+    let moisture = noise::fbm(seed.wrapping_add(0x100), center * MOISTURE_SCALE, 3);
+    let river = river_distance(seed, center);
+    if elevation < WATER_LEVEL || river < RIVER_WIDTH {
+        Tile::Water
+    } else if river < SHALLOWS_WIDTH {
+        Tile::Shallows
+    } else if elevation > ROCK_LEVEL {
+        Tile::Rock
+    } else if moisture < DRY_LEVEL {
+        Tile::Dirt
+    } else {
+        Tile::Grass
+    }
+}
+
+// Ground tiles grouped into chunks. Each chunk is drawn as one mesh per
+// tile type and rebuilt only when one of its tiles changes.
+#[derive(Resource)]
+pub struct GroundMap {
+    pub seed: u64,
+    chunks: HashMap<IVec2, GroundChunk>,
+    generation: u64  // bumped whenever any tile appears, disappears or changes
+} impl Default for GroundMap {
+    fn default() -> Self {
+        Self {
+            seed: SEED,
+            chunks: HashMap::new(),
+            generation: 0
+        }
+    }
+} impl GroundMap {
+    pub fn tile_coord(position: Vec2) -> IVec2 {
+        (position / TILE_SIZE).floor().as_ivec2()
+    }
+    pub fn chunk_coord(position: Vec2) -> IVec2 {
+        Self::tile_coord(position).div_euclid(IVec2::splat(CHUNK_TILES))
+    }
+    pub fn chunk_rect(coord: IVec2) -> Rect {
+        let min = coord.as_vec2() * CHUNK_SIZE;
+        Rect::from_corners(min, min + CHUNK_SIZE)
+    }
+    // Generates the chunk's tiles from the seed, unless already loaded.
+    pub fn generate_chunk(&mut self, coord: IVec2) {
+        if !self.chunks.contains_key(&coord) {
+            self.chunks.insert(coord, GroundChunk::generate(self.seed, coord));
+            self.generation += 1;
+        };
+    }
+    // Loads the chunk with tiles saved by `edited_tiles`, unless already
+    // loaded. Tiles that don't fill a chunk are ignored and it's generated
+    // from the seed instead.
+    pub fn restore_chunk(&mut self, coord: IVec2, tiles: Vec<Tile>) {
+        if tiles.len() != (CHUNK_TILES * CHUNK_TILES) as usize {
+            self.generate_chunk(coord);
+            return;
+        };
+        if let Entry::Vacant(entry) = self.chunks.entry(coord) {
+            entry.insert(GroundChunk {
+                tiles,
+                dirty: true,
+                edited: true,
+                entities: Vec::new()
+            });
+            self.generation += 1;
+        };
+    }
+    // The chunk's tiles if any were set since it was generated, for saving
+    // before it's unloaded.
+    pub fn edited_tiles(&self, coord: IVec2) -> Option<Vec<Tile>> {
+        self.chunks.get(&coord).filter(|chunk| chunk.edited).map(|chunk| chunk.tiles.clone())
+    }
+    // Drops the chunk, returning its mesh entities for despawning. Tiles are
+    // regenerated from the seed when it comes back, unless restored.
+    pub fn unload_chunk(&mut self, coord: IVec2) -> Vec<Entity> {
+        let Some(chunk) = self.chunks.remove(&coord) else {
+            return Vec::new();
+        };
+        self.generation += 1;
+        chunk.entities
+    }
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+    // `None` off the generated map.
+    pub fn tile_at(&self, position: Vec2) -> Option<Tile> {
+        let tile = Self::tile_coord(position);
+        let chunk = self.chunks.get(&tile.div_euclid(IVec2::splat(CHUNK_TILES)))?;
+        Some(chunk.tiles[GroundChunk::index(tile.rem_euclid(IVec2::splat(CHUNK_TILES)))])
+    }
+    pub fn ground_height(&self, position: Vec2) -> f32 {
+        ground_height(self.seed, position)
+    }
+    pub fn is_walkable(&self, position: Vec2) -> bool {
+        self.tile_at(position).is_some_and(|tile| tile.walkable())
+    }
+    pub fn is_fertile(&self, position: Vec2) -> bool {
+        self.tile_at(position).is_some_and(|tile| tile.fertile())
+    }
+    // 0.0 off the generated map.
+    pub fn speed_factor(&self, position: Vec2) -> f32 {
+        self.tile_at(position).map_or(0.0, |tile| tile.speed_factor())
+    }
+    pub fn set_tile(&mut self, position: Vec2, tile: Tile) {
+        let coord = Self::tile_coord(position);
+        let Some(chunk) = self.chunks.get_mut(&coord.div_euclid(IVec2::splat(CHUNK_TILES))) else {
+            return;
+        };
+        let index = GroundChunk::index(coord.rem_euclid(IVec2::splat(CHUNK_TILES)));
+        if chunk.tiles[index] != tile {
+            chunk.tiles[index] = tile;
+            chunk.dirty = true;
+            chunk.edited = true;
+            self.generation += 1;
+        };
+    }
+}
+
+// Keeps an entity standing on the terrain, `offset` above the ground.
+#[derive(Component, Default)]
+pub struct GroundFollower {
+    pub offset: f32
+}
+
+#[derive(Component)]
+pub struct GroundChunkMesh {
+    pub coord: IVec2,
+    pub tile: Tile
+}
+
+#[derive(Resource)]
+struct GroundMaterials {
+    materials: HashMap<Tile, Handle<StandardMaterial>>
+}
+
+pub struct GroundPlugin;
+impl Plugin for GroundPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GroundMap>();
+        app.add_systems(PreStartup, create_ground_materials);
+        app.add_systems(Update, rebuild_dirty_chunks);
+        app.add_systems(PostUpdate, follow_ground.before(TransformSystem::TransformPropagate));
+    }
+}
+
+fn create_ground_materials(
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut commands: Commands
+) {
+    let materials = Tile::ALL.into_iter().map(|tile| (tile, materials.add(tile.material()))).collect();
+    commands.insert_resource(GroundMaterials {materials});
+}
+
+// One quad per matching tile, in world space, its corners on the terrain.
+fn chunk_mesh(seed: u64, coord: IVec2, chunk: &GroundChunk, tile: Tile) -> Option<Mesh> {
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut indices = Vec::new();
+    for y in 0..CHUNK_TILES {
+        for x in 0..CHUNK_TILES {
+            if chunk.tiles[GroundChunk::index(IVec2::new(x, y))] != tile {
+                continue;
+            };
+            let min = (coord * CHUNK_TILES + IVec2::new(x, y)).as_vec2() * TILE_SIZE;
+            let start = positions.len() as u32;
+            for corner in [Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::Y] {
+                let position = min + corner * TILE_SIZE;
+                positions.push(position.extend(ground_height(seed, position)).to_array());
+                normals.push(ground_normal(seed, position).to_array());
+            }
+            indices.extend([start, start + 1, start + 2, start, start + 2, start + 3]);
+        }
+    }
+    if positions.is_empty() {
+        return None;
+    };
+    let uvs: Vec<[f32; 2]> = positions.iter().map(|p| [p[0], p[1]]).collect();
+    Some(Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+        .with_inserted_indices(Indices::U32(indices)))
+}
+
+fn rebuild_dirty_chunks(
+    ground_materials: Option<Res<GroundMaterials>>,
+    mut ground: ResMut<GroundMap>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut commands: Commands
+) {
+    let Some(ground_materials) = ground_materials else {
+        return;
+    };
+    let seed = ground.seed;
+    for (&coord, chunk) in ground.chunks.iter_mut().filter(|(_, chunk)| chunk.dirty) {
+        for entity in chunk.entities.drain(..) {
+            commands.entity(entity).try_despawn();
+        }
+        for tile in Tile::ALL {
+            let Some(mesh) = chunk_mesh(seed, coord, chunk, tile) else {
+                continue;
+            };
+            let entity = commands.spawn((
+                Mesh3d(meshes.add(mesh)),
+                MeshMaterial3d(ground_materials.materials[&tile].clone()),
+                Transform::IDENTITY,
+                NotShadowCaster,
+                GroundChunkMesh {coord, tile}
+            )).id();
+            chunk.entities.push(entity);
+        }
+        chunk.dirty = false;
+    }
+}
+
+type MovedFollowers<'w, 's> = Query<'w, 's, (&'static mut Transform, &'static GroundFollower), Or<(Changed<Transform>, Changed<GroundFollower>)>>;
+
+pub fn follow_ground(
+    ground: Res<GroundMap>,
+    follower_query: MovedFollowers
+) {
+    for (mut transform, follower) in follower_query {
+        let z = ground.ground_height(transform.translation.xy()) + follower.offset;
+        if transform.translation.z != z {
+            transform.translation.z = z;
+        };
+    }
+}
@@ -0,0 +1,25 @@
+use ::bevy::prelude::*;
+
+mod macros;
+pub mod animal;
+pub mod camera_module;
+pub mod collision;
+pub mod common;
+pub mod player_controls;
+pub mod decoration;
+pub mod ground;
+pub mod navigation;
+pub mod noise;
+pub mod streaming;
+pub mod environment;
+pub mod tree;
+pub mod tuning;
+pub mod model_previewer;
+pub mod assets;
+pub mod audio;
+
+trait Model {
+    type Output;
+
+    fn simulate(self) -> Self::Output;
+}
@@ -2,9 +2,12 @@ use bevy::prelude::*;
 use bevy::core_pipeline::bloom::Bloom;
 use bevy::core_pipeline::tonemapping::Tonemapping;
 use bevy::render::camera::ScalingMode;
-use std::f32::consts::FRAC_PI_2;
+use bevy::render::view::screenshot::{save_to_disk, Screenshot};
+use std::f32::consts::{FRAC_PI_2, TAU};
+use std::path::{Path, PathBuf};
 use bevy::input::mouse::{MouseMotion, MouseWheel};
 use bevy::pbr::{NotShadowCaster, NotShadowReceiver};
+use crate::assets::{PropDespawn, PropIOU};
 
 // CAMERA
 const FOV: f32 = FRAC_PI_2;
@@ -16,8 +19,8 @@ const CAMERA_SPEED: f32 = 0.5;
 const ZOOM_SPEED: f32 = 1.1;
 
 // CHECKERS
-const SPAWN_PLANE: bool = true;
-const SPAWN_GRID: bool = false;
+const SHOW_PLANE: bool = true;
+const SHOW_GRID: bool = false;
 const TILE_Z: f32 = 0.0;
 const GRID_Z: f32 = -CAMERA_DISTANCE * 2.0;
 const TILING_RADIUS: isize = 4;
@@ -28,17 +31,73 @@ const COLORS: [Color; 2] = [
 ];
 
 // LIGHT
-const SPAWN_LIGHT: bool = true;
+const SHOW_LIGHT: bool = true;
 const LIGHT_POS: Vec3 = Vec3::new(0.0, 0.0, 1.0);
 const LIGHT_BRIGHTNESS: f32 = 1000.0;
 const AMBIENT_LIGHT: f32 = 100.0;
 
+// PROP
+const MOVE_RADIUS: f32 = 1.0;  // the prop walks a circle passing through the origin
+const MOVE_SPEED: f32 = 2.0;  // radians per second
+
+// TURNTABLE
+const TURNTABLE_STEPS: usize = 36;  // frames captured per revolution
+const TURNTABLE_DIRECTORY: &str = "turntable";  // frames land in `<this>/<prop name>/`
+
+// CONTROLS
+const NEXT_PROP: KeyCode = KeyCode::ArrowRight;
+const PREVIOUS_PROP: KeyCode = KeyCode::ArrowLeft;
+const TOGGLE_MOVING: KeyCode = KeyCode::Space;
+const TOGGLE_PERSPECTIVE: KeyCode = KeyCode::KeyP;
+const TOGGLE_PLANE: KeyCode = KeyCode::KeyF;
+const TOGGLE_GRID: KeyCode = KeyCode::KeyG;
+const TOGGLE_LIGHT: KeyCode = KeyCode::KeyL;
+const START_TURNTABLE: KeyCode = KeyCode::KeyT;
+
 pub struct ModelViewerPlugin;
 impl Plugin for ModelViewerPlugin {
     fn build(&self, app: &mut App) {
+        app.insert_resource(PreviewSettings::default());
         app.add_systems(Startup, setup);
-        app.add_systems(Update, camera_rotation);
+        app.add_systems(Update, camera_rotation.run_if(not(resource_exists::<Turntable>)));
         app.add_systems(Update, camera_zoom);
+        app.add_systems(Update, preview_controls.run_if(not(resource_exists::<Turntable>)));
+        app.add_systems(Update, start_turntable.run_if(not(resource_exists::<Turntable>)));
+        app.add_systems(Update, turntable_capture
+            .after(start_turntable)
+            .run_if(resource_exists::<Turntable>)
+        );
+        app.add_systems(Update, apply_preview_settings
+            .after(preview_controls)
+            .run_if(resource_changed::<PreviewSettings>)
+        );
+        app.add_systems(Update, apply_projection
+            .after(preview_controls)
+            .after(camera_zoom)
+            .run_if(resource_changed::<PreviewSettings>.or(resource_changed::<CameraZoom>))
+        );
+        app.add_systems(Update, move_preview_prop.after(preview_controls));
+    }
+}
+
+#[derive(Resource)]
+struct PreviewSettings {
+    prop_index: usize,
+    moving: bool,
+    perspective: bool,
+    plane: bool,
+    grid: bool,
+    light: bool
+} impl Default for PreviewSettings {
+    fn default() -> Self {
+        Self {
+            prop_index: 0,
+            moving: false,
+            perspective: USE_PERSPECTIVE,
+            plane: SHOW_PLANE,
+            grid: SHOW_GRID,
+            light: SHOW_LIGHT
+        }
     }
 }
 
@@ -49,7 +108,15 @@ struct RotSums {
 
 #[derive(Resource)]
 struct CameraZoom{
-    f32: f32
+    fov: f32,
+    width: f32
+}
+
+// Present while a turntable capture is running, removed after the last frame.
+#[derive(Resource)]
+struct Turntable {
+    directory: PathBuf,
+    step: usize
 }
 
 #[derive(Component)]
@@ -58,6 +125,29 @@ struct CameraCenter;
 #[derive(Component)]
 struct POVCam;
 
+#[derive(Component)]
+struct PreviewPlane;
+
+#[derive(Component)]
+struct PreviewGrid;
+
+#[derive(Component)]
+struct PreviewLight;
+
+#[derive(Component)]
+struct PreviewProp {
+    angle: f32
+}
+
+fn spawn_preview_prop(commands: &mut Commands, iou: PropIOU) {
+    commands.spawn((
+        Transform::default(),
+        Visibility::default(),
+        iou,
+        PreviewProp {angle: -FRAC_PI_2}
+    ));
+}
+
 fn setup(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
@@ -65,6 +155,7 @@ fn setup(
 ) {
     // CAMERA
     commands.insert_resource(RotSums{vec: Vec2::ZERO});
+    commands.insert_resource(CameraZoom{fov: FOV, width: VIEW_WIDTH});
     let camera_center = commands.spawn((
        Transform::from_xyz(0.0, 0.0, CAMERA_Z),
        CameraCenter
@@ -80,70 +171,36 @@ fn setup(
         ChildOf(camera_center),
         POVCam
     )).id();
-    if USE_PERSPECTIVE {
-        commands.insert_resource(CameraZoom{f32: FOV});
-        commands.entity(camera).insert(
-            Projection::Perspective(
-                PerspectiveProjection {
-                    fov: FOV,
-                    ..default()
-                }
-            )
-        );
-    } else {
-        commands.insert_resource(CameraZoom{f32: VIEW_WIDTH});
-        commands.entity(camera).insert(
-            Projection::Orthographic(
-                OrthographicProjection {
-                    scaling_mode: ScalingMode::FixedHorizontal {viewport_width: VIEW_WIDTH },
-                    ..OrthographicProjection::default_3d()
-                }
-            )
-        );
-    };
 
     // PLANE AND GRID
     let tile_mesh = meshes.add(Rectangle::from_length(TILE_DIAMETER));
     let mat_a = materials.add(StandardMaterial::from_color(COLORS[0]));
     let mat_b = materials.add(StandardMaterial::from_color(COLORS[1]));
-    if SPAWN_PLANE {
-        for xi in -TILING_RADIUS..=TILING_RADIUS {
-            let x = xi as f32 * TILE_DIAMETER;
-            for yi in -TILING_RADIUS..=TILING_RADIUS {
-                let y = yi as f32 * TILE_DIAMETER;
-                let mat = if (xi + yi) % 2 == 0 {
-                    mat_a.clone()
-                } else {
-                    mat_b.clone()
-                };
-                commands.spawn((
-                    Transform::from_xyz(x, y, TILE_Z),
-                    Mesh3d(tile_mesh.clone()),
-                    MeshMaterial3d(mat),
-                    NotShadowCaster
-                ));
-            };
-        };
-    };
-    if SPAWN_GRID {
-        for xi in -TILING_RADIUS..=TILING_RADIUS {
-            let x = xi as f32 * TILE_DIAMETER;
-            for yi in -TILING_RADIUS..=TILING_RADIUS {
-                let y = yi as f32 * TILE_DIAMETER;
-                let mat = if (xi + yi) % 2 == 0 {
-                    mat_a.clone()
-                } else {
-                    mat_b.clone()
-                };
-                commands.spawn((
-                    Transform::from_xyz(x, y, GRID_Z),
-                    Mesh3d(tile_mesh.clone()),
-                    MeshMaterial3d(mat),
-                    NotShadowCaster,
-                    NotShadowReceiver,
-                    ChildOf(camera)
-                ));
+    for xi in -TILING_RADIUS..=TILING_RADIUS {
+        let x = xi as f32 * TILE_DIAMETER;
+        for yi in -TILING_RADIUS..=TILING_RADIUS {
+            let y = yi as f32 * TILE_DIAMETER;
+            let mat = if (xi + yi) % 2 == 0 {
+                mat_a.clone()
+            } else {
+                mat_b.clone()
             };
+            commands.spawn((
+                Transform::from_xyz(x, y, TILE_Z),
+                Mesh3d(tile_mesh.clone()),
+                MeshMaterial3d(mat.clone()),
+                NotShadowCaster,
+                PreviewPlane
+            ));
+            commands.spawn((
+                Transform::from_xyz(x, y, GRID_Z),
+                Mesh3d(tile_mesh.clone()),
+                MeshMaterial3d(mat),
+                NotShadowCaster,
+                NotShadowReceiver,
+                ChildOf(camera),
+                PreviewGrid
+            ));
         };
     };
 
@@ -156,13 +213,98 @@ fn setup(
             shadows_enabled: true,
             ..default()
         },
-        Transform::from_translation(LIGHT_POS).looking_at(Vec3::ZERO, Vec3::Y)
+        Transform::from_translation(LIGHT_POS).looking_at(Vec3::ZERO, Vec3::Y),
+        PreviewLight
     ));
+
+    // PROP
+    spawn_preview_prop(&mut commands, PropIOU::ALL[0]);
+}
+
+fn preview_controls(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<PreviewSettings>,
+    prop_query: Query<Entity, With<PreviewProp>>,
+    mut despawn_writer: EventWriter<PropDespawn>,
+    mut commands: Commands
+) {
+    let count = PropIOU::ALL.len();
+    let prop_index = if keys.just_pressed(NEXT_PROP) {
+        (settings.prop_index + 1) % count
+    } else if keys.just_pressed(PREVIOUS_PROP) {
+        (settings.prop_index + count - 1) % count
+    } else {
+        settings.prop_index
+    };
+    if prop_index != settings.prop_index {
+        for entity in prop_query {
+            despawn_writer.write(PropDespawn {entity, fade: None});
+        };
+        spawn_preview_prop(&mut commands, PropIOU::ALL[prop_index]);
+        settings.prop_index = prop_index;
+    };
+    if keys.just_pressed(TOGGLE_MOVING) {
+        settings.moving = !settings.moving;
+    };
+    if keys.just_pressed(TOGGLE_PERSPECTIVE) {
+        settings.perspective = !settings.perspective;
+    };
+    if keys.just_pressed(TOGGLE_PLANE) {
+        settings.plane = !settings.plane;
+    };
+    if keys.just_pressed(TOGGLE_GRID) {
+        settings.grid = !settings.grid;
+    };
+    if keys.just_pressed(TOGGLE_LIGHT) {
+        settings.light = !settings.light;
+    };
+}
+
+fn apply_preview_settings(
+    settings: Res<PreviewSettings>,
+    plane_query: Query<&mut Visibility, (With<PreviewPlane>, Without<PreviewGrid>)>,
+    grid_query: Query<&mut Visibility, (With<PreviewGrid>, Without<PreviewPlane>)>,
+    light_query: Query<&mut DirectionalLight, With<PreviewLight>>
+) {
+    let visibility = |shown: bool| if shown {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    for mut v in plane_query {
+        *v = visibility(settings.plane);
+    };
+    for mut v in grid_query {
+        *v = visibility(settings.grid);
+    };
+    for mut light in light_query {
+        light.illuminance = if settings.light {
+            LIGHT_BRIGHTNESS
+        } else {
+            0.0
+        };
+    };
+}
+
+// Walks the previewed prop around a circle so its move animation plays,
+// and leaves it standing wherever it stopped for the idle animation.
+fn move_preview_prop(
+    time: Res<Time>,
+    settings: Res<PreviewSettings>,
+    prop_query: Query<(&mut Transform, &mut PreviewProp)>
+) {
+    if !settings.moving {
+        return;
+    };
+    for (mut transform, mut prop) in prop_query {
+        prop.angle = (prop.angle + MOVE_SPEED * time.delta_secs()) % TAU;
+        let offset = Vec2::new(prop.angle.cos(), prop.angle.sin() + 1.0) * MOVE_RADIUS;
+        transform.translation = offset.extend(transform.translation.z);
+    };
 }
 
 fn camera_rotation(
     time: Res<Time>,
-    keys: Res<ButtonInput<KeyCode>>,
     mouse_buttons: Res<ButtonInput<MouseButton>>,
     mut mouse_motion: EventReader<MouseMotion>,
     mut center_query: Query<&mut Transform, With<CameraCenter>>,
@@ -176,35 +318,91 @@ fn camera_rotation(
         };
         sum *= -(CAMERA_SPEED * time.delta_secs());
         rot_sums.vec += sum;
-        center_transform.rotation = Quat::from_euler(EulerRot::ZXY, rot_sums.vec.x, rot_sums.vec.y, 0.0);
+        center_transform.rotation = orbit_rotation(rot_sums.vec);
     } else {
         mouse_motion.clear()
     };
 }
 
+fn orbit_rotation(rot_sums: Vec2) -> Quat {
+    Quat::from_euler(EulerRot::ZXY, rot_sums.x, rot_sums.y, 0.0)
+}
+
+fn start_turntable(
+    keys: Res<ButtonInput<KeyCode>>,
+    settings: Res<PreviewSettings>,
+    mut commands: Commands
+) {
+    if !keys.just_pressed(START_TURNTABLE) {
+        return;
+    };
+    let iou = PropIOU::ALL[settings.prop_index];
+    let directory = Path::new(TURNTABLE_DIRECTORY).join(iou.name());
+    if let Err(e) = std::fs::create_dir_all(&directory) {
+        error!("cannot create turntable directory {}: {e}", directory.display());
+        return;
+    };
+    commands.insert_resource(Turntable {directory, step: 0});
+}
+
+// Steps `CameraCenter` a fraction of a revolution around the prop each
+// frame and screenshots it, then hands the camera back to the mouse.
+fn turntable_capture(
+    mut turntable: ResMut<Turntable>,
+    rot_sums: Res<RotSums>,
+    mut center_query: Query<&mut Transform, With<CameraCenter>>,
+    mut commands: Commands
+) {
+    let mut center_transform = center_query.single_mut().unwrap();
+    if turntable.step >= TURNTABLE_STEPS {
+        center_transform.rotation = orbit_rotation(rot_sums.vec);
+        info!("turntable saved to {}", turntable.directory.display());
+        commands.remove_resource::<Turntable>();
+        return;
+    };
+    let angle = turntable.step as f32 * TAU / TURNTABLE_STEPS as f32;
+    center_transform.rotation = orbit_rotation(rot_sums.vec + Vec2::new(angle, 0.0));
+    let path = turntable.directory.join(format!("frame_{:03}.png", turntable.step));
+    commands.spawn(Screenshot::primary_window()).observe(save_to_disk(path));
+    turntable.step += 1;
+}
+
 fn camera_zoom(
-    time: Res<Time>,
+    settings: Res<PreviewSettings>,
     mut mouse_scrolling: EventReader<MouseWheel>,
-    projection_query: Query<Entity, With<POVCam>>,
-    mut camera_zoom: ResMut<CameraZoom>,
-    mut commands: Commands
+    mut camera_zoom: ResMut<CameraZoom>
 ) {
     if mouse_scrolling.is_empty() {
         return;
     };
+    let zoom = if settings.perspective {
+        &mut camera_zoom.fov
+    } else {
+        &mut camera_zoom.width
+    };
     for event in mouse_scrolling.read() {
         if event.y > 0.0 {
-            camera_zoom.f32 /= ZOOM_SPEED;
+            *zoom /= ZOOM_SPEED;
         } else if event.y < 0.0 {
-            camera_zoom.f32 *= ZOOM_SPEED;
+            *zoom *= ZOOM_SPEED;
         };
     };
-    let entity = projection_query.single().unwrap();
-    if USE_PERSPECTIVE {
+}
+
+fn apply_projection(
+    settings: Res<PreviewSettings>,
+    camera_zoom: Res<CameraZoom>,
+    projection_query: Query<Entity, With<POVCam>>,
+    mut commands: Commands
+) {
+    let Ok(entity) = projection_query.single() else {
+        return;
+    };
+    if settings.perspective {
         commands.entity(entity).insert(
             Projection::Perspective(
                 PerspectiveProjection {
-                    fov: camera_zoom.f32,
+                    fov: camera_zoom.fov,
                     ..default()
                 }
             )
@@ -213,10 +411,10 @@ fn camera_zoom(
         commands.entity(entity).insert(
             Projection::Orthographic(
                 OrthographicProjection {
-                    scaling_mode: ScalingMode::FixedHorizontal {viewport_width: camera_zoom.f32 },
+                    scaling_mode: ScalingMode::FixedHorizontal {viewport_width: camera_zoom.width },
                     ..OrthographicProjection::default_3d()
                 }
             )
         );
     }
-}
\ No newline at end of file
+}
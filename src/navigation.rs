@@ -0,0 +1,329 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use bevy::prelude::*;
+use serde::Deserialize;
+use crate::ground::{GroundMap, TILE_SIZE};
+use crate::tree::Tree;
+
+mod flow_field;
+pub use flow_field::{FlowField, FlowFields, FlowGoal};
+
+const MAX_SEARCH_NODES: usize = 4096;  // gives up on goals further or more walled off than this
+const MAX_CACHED_PATHS: usize = 1024;  // forgets every cached path once this many pile up
+const NEIGHBOURS: [IVec2; 8] = [
+    IVec2::new(1, 0), IVec2::new(-1, 0), IVec2::new(0, 1), IVec2::new(0, -1),
+    IVec2::new(1, 1), IVec2::new(1, -1), IVec2::new(-1, 1), IVec2::new(-1, -1)
+];
+
+// Waypoints towards `goal`, consumed as they are reached.
+#[derive(Clone, Debug)]
+pub struct Path {
+    pub waypoints: VecDeque<Vec2>,
+    pub goal: Vec2,
+    version: Option<u64>  // of the `NavGrid` it was planned on, `None` if it wasn't
+} impl Path {
+    // Straight at the goal, for when there's no grid to plan on.
+    pub fn straight(goal: Vec2) -> Self {
+        Self {
+            waypoints: VecDeque::from([goal]),
+            goal,
+            version: None
+        }
+    }
+    pub fn is_finished(&self) -> bool {
+        self.waypoints.is_empty()
+    }
+    // Planned on a grid that has changed since.
+    pub fn is_stale(&self, grid: &NavGrid) -> bool {
+        self.version.is_some_and(|version| version != grid.version)
+    }
+    // Moves `distance` from `position` along the path, dropping the
+    // waypoints passed on the way.
+    pub fn advance(&mut self, mut position: Vec2, mut distance: f32) -> Vec2 {
+        while let Some(&waypoint) = self.waypoints.front() {
+            let remaining = position.distance(waypoint);
+            if remaining > distance {
+                return position + (waypoint - position) / remaining * distance;
+            };
+            position = waypoint;
+            distance -= remaining;
+            self.waypoints.pop_front();
+        }
+        position
+    }
+}
+
+// How animals find their way to one kind of goal.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Deserialize)]
+pub enum Method {
+    AStar,  // a path of their own, best for a few animals
+    FlowField  // a field shared by everyone heading the same way, best for herds
+}
+
+// Method used for each goal, switchable live from the tuning file.
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(default)]
+pub struct Steering {
+    pub nearest_tree: Method,
+    pub gather: Method,
+    pub flee: Method
+} impl Default for Steering {
+    fn default() -> Self {
+        Self {
+            nearest_tree: Method::FlowField,
+            gather: Method::FlowField,
+            flee: Method::FlowField
+        }
+    }
+} impl Steering {
+    pub fn method(&self, goal: FlowGoal) -> Method {
+        match goal {
+            FlowGoal::NearestTree => self.nearest_tree,
+            FlowGoal::Gather(_) => self.gather,
+            FlowGoal::Flee => self.flee
+        }
+    }
+}
+
+// Follows the field for `goal` until its cost reaches `until`, i.e. until
+// close enough to the goal, or far enough away for goals that ascend.
+#[derive(Clone, Debug)]
+pub struct FlowRoute {
+    pub goal: FlowGoal,
+    pub until: f32,
+    finished: bool
+} impl FlowRoute {
+    pub fn new(goal: FlowGoal, until: f32) -> Self {
+        Self {
+            goal,
+            until,
+            finished: false
+        }
+    }
+    // Gives `None` when the animal is lost: the field is gone, doesn't
+    // cover where it stands, or leads nowhere from there.
+    fn advance(&mut self, flow_fields: Option<&FlowFields>, position: Vec2, distance: f32) -> Option<Vec2> {
+        let field = flow_fields?.get(self.goal)?;
+        let ascends = self.goal.ascends();
+        let cost = field.cost(position)?;
+        if (ascends && cost >= self.until) || (!ascends && cost <= self.until) {
+            self.finished = true;
+            return Some(position);
+        };
+        let direction = field.direction(position, ascends)?;
+        Some(position + direction * distance)
+    }
+}
+
+// Either a planned path or a shared flow field, so callers can move an
+// animal along whichever `Method` its goal uses.
+#[derive(Clone, Debug)]
+pub enum Route {
+    Path(Path),
+    Flow(FlowRoute)
+} impl Route {
+    pub fn is_finished(&self) -> bool {
+        match self {
+            Self::Path(path) => path.is_finished(),
+            Self::Flow(flow) => flow.finished
+        }
+    }
+    // Moves `distance` from `position` along the route. Paths planned on a
+    // grid that has since changed are re-planned first, giving `None` if
+    // there's no longer a way, as does a flow field that is lost.
+    pub fn advance(&mut self, grid: Option<&mut NavGrid>, ground: Option<&GroundMap>, flow_fields: Option<&FlowFields>, position: Vec2, distance: f32) -> Option<Vec2> {
+        match self {
+            Self::Path(path) => {
+                if let (Some(grid), Some(ground)) = (grid, ground) && path.is_stale(grid) {
+                    *path = grid.find_path(ground, position, path.goal)?;
+                };
+                Some(path.advance(position, distance))
+            },
+            Self::Flow(flow) => flow.advance(flow_fields, position, distance)
+        }
+    }
+}
+
+// Passable 8-connected neighbours of `cell` with the cost of stepping onto
+// them, leaving out diagonals that would clip the corner of a blocked cell.
+fn neighbours(cell: IVec2, passable: impl Fn(IVec2) -> Option<f32>) -> impl Iterator<Item = (IVec2, f32)> {
+    NEIGHBOURS.into_iter().filter_map(move |offset| {
+        let step_cost = passable(cell + offset)?;
+        let diagonal = offset.x != 0 && offset.y != 0;
+        if diagonal && (passable(cell + IVec2::new(offset.x, 0)).is_none() || passable(cell + IVec2::new(0, offset.y)).is_none()) {
+            return None;
+        };
+        Some((cell + offset, step_cost * offset.as_vec2().length()))
+    })
+}
+
+// Open set entry, ordered so the `BinaryHeap` pops the lowest estimate.
+struct Node {
+    estimate: f32,
+    cell: IVec2
+} impl PartialEq for Node {
+    fn eq(&self, other: &Self) -> bool {
+        self.estimate == other.estimate
+    }
+} impl Eq for Node {} impl PartialOrd for Node {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+} impl Ord for Node {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.total_cmp(&self.estimate)
+    }
+}
+
+// Walkability of every ground tile, minus the tiles standing trees grow
+// on. Paths are found with A* and cached until the grid next changes.
+#[derive(Resource, Default)]
+pub struct NavGrid {
+    blocked: HashSet<IVec2>,
+    ground_generation: u64,
+    version: u64,
+    cache: HashMap<(IVec2, IVec2), Option<Vec<Vec2>>>
+} impl NavGrid {
+    pub fn cell(position: Vec2) -> IVec2 {
+        GroundMap::tile_coord(position)
+    }
+    pub fn cell_center(cell: IVec2) -> Vec2 {
+        (cell.as_vec2() + 0.5) * TILE_SIZE
+    }
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+    // Cost of crossing the cell, `None` if it can't be crossed at all.
+    // Shallow water costs more, so paths go around it when they can.
+    pub fn cost(&self, ground: &GroundMap, cell: IVec2) -> Option<f32> {
+        if self.blocked.contains(&cell) {
+            return None;
+        };
+        let speed_factor = ground.speed_factor(Self::cell_center(cell));
+        (speed_factor > 0.0).then(|| speed_factor.recip())
+    }
+    // Replaces the blocked cells, bumping the version if anything changed.
+    fn update(&mut self, ground_generation: u64, blocked: HashSet<IVec2>) {
+        if ground_generation == self.ground_generation && blocked == self.blocked {
+            return;
+        };
+        self.ground_generation = ground_generation;
+        self.blocked = blocked;
+        self.version += 1;
+        self.cache.clear();
+    }
+    // Plans a path from `from` to `to`. The goal's own cell may be blocked,
+    // so animals can path right up to a tree.
+    pub fn find_path(&mut self, ground: &GroundMap, from: Vec2, to: Vec2) -> Option<Path> {
+        let key = (Self::cell(from), Self::cell(to));
+        let cells = match self.cache.get(&key) {
+            Some(cells) => cells.clone(),
+            None => {
+                let cells = self.search(ground, key.0, key.1);
+                if self.cache.len() >= MAX_CACHED_PATHS {
+                    self.cache.clear();
+                }
+                self.cache.insert(key, cells.clone());
+                cells
+            }
+        }?;
+        let mut waypoints: VecDeque<Vec2> = cells.into();
+        waypoints.pop_back();
+        waypoints.push_back(to);
+        Some(Path {
+            waypoints,
+            goal: to,
+            version: Some(self.version)
+        })
+    }
+    // A* over the grid's `neighbours`. Returns the centres of the cells
+    // after `start` up to and including `goal`, with the cells in straight
+    // runs left out.
+    fn search(&self, ground: &GroundMap, start: IVec2, goal: IVec2) -> Option<Vec<Vec2>> {
+        if start == goal {
+            return Some(vec![Self::cell_center(goal)]);
+        };
+        let passable = |cell: IVec2| -> Option<f32> {
+            if cell == goal {
+                let speed_factor = ground.speed_factor(Self::cell_center(cell));
+                return (speed_factor > 0.0).then(|| speed_factor.recip());
+            };
+            self.cost(ground, cell)
+        };
+        passable(goal)?;
+        let heuristic = |cell: IVec2| -> f32 {
+            let delta = (goal - cell).abs();
+            (delta.max_element() - delta.min_element()) as f32 + delta.min_element() as f32 * std::f32::consts::SQRT_2
+        };
+        let mut open = BinaryHeap::from([Node {estimate: heuristic(start), cell: start}]);
+        let mut costs = HashMap::from([(start, 0.0)]);
+        let mut came_from: HashMap<IVec2, IVec2> = HashMap::new();
+        let mut expanded = 0;
+        while let Some(Node {cell, ..}) = open.pop() {
+            if cell == goal {
+                break;
+            };
+            expanded += 1;
+            if expanded > MAX_SEARCH_NODES {
+                return None;
+            };
+            let cost = costs[&cell];
+            for (next, step_cost) in neighbours(cell, &passable) {
+                let next_cost = cost + step_cost;
+                if costs.get(&next).is_some_and(|&known| known <= next_cost) {
+                    continue;
+                };
+                costs.insert(next, next_cost);
+                came_from.insert(next, cell);
+                open.push(Node {estimate: next_cost + heuristic(next), cell: next});
+            }
+        }
+        if !came_from.contains_key(&goal) {
+            return None;
+        };
+        let mut cells = vec![goal];
+        let mut cell = goal;
+        while let Some(&previous) = came_from.get(&cell) {
+            if previous == start {
+                break;
+            };
+            cells.push(previous);
+            cell = previous;
+        }
+        cells.reverse();
+        let mut waypoints = Vec::new();
+        for (index, &cell) in cells.iter().enumerate() {
+            let before = if index == 0 {start} else {cells[index - 1]};
+            let straight = cells.get(index + 1).is_some_and(|&after| after - cell == cell - before);
+            if !straight {
+                waypoints.push(Self::cell_center(cell));
+            };
+        }
+        Some(waypoints)
+    }
+}
+
+pub struct NavigationPlugin;
+impl Plugin for NavigationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NavGrid>();
+        app.init_resource::<FlowFields>();
+        app.add_systems(PreUpdate, (update_nav_grid, flow_field::update_flow_fields).chain());
+    }
+}
+
+// Rebuilds the blocked cells from the trees, stumps included as they keep
+// their collider, so a tree sprouting, or ground streaming in or out,
+// invalidates cached paths.
+fn update_nav_grid(
+    ground: Option<Res<GroundMap>>,
+    tree_query: Query<&Transform, With<Tree>>,
+    mut grid: ResMut<NavGrid>
+) {
+    let ground_generation = ground.map_or(0, |ground| ground.generation());
+    let blocked = tree_query
+        .iter()
+        .map(|transform| NavGrid::cell(transform.translation.xy()))
+        .collect();
+    grid.update(ground_generation, blocked);
+}
@@ -0,0 +1,137 @@
+use std::collections::{BinaryHeap, HashMap};
+use bevy::prelude::*;
+use crate::animal::{Animal, DeathLifecycle, DeathTracker, Species};
+use crate::ground::GroundMap;
+use crate::player_controls::PlayerMotion;
+use crate::tree::{HealthTracker, Tree};
+use super::{NavGrid, Node, neighbours};
+
+const REFRESH_SECONDS: f32 = 0.5;  // goals that move, like the player, are re-flooded this often
+const MAX_FIELD_CELLS: usize = 16_384;  // about the loaded area around the camera
+
+// A shared destination many animals steer towards (or away from) at once.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum FlowGoal {
+    NearestTree,
+    Gather(Species),  // the middle of the species' herd
+    Flee  // away from the player
+} impl FlowGoal {
+    // Whether animals follow the field uphill, away from its sources.
+    pub fn ascends(&self) -> bool {
+        matches!(self, Self::Flee)
+    }
+}
+
+// Cost of the cheapest walk from every reachable cell to the nearest of
+// the field's sources.
+#[derive(Default)]
+pub struct FlowField {
+    costs: HashMap<IVec2, f32>
+} impl FlowField {
+    // Floods outwards from `sources` with Dijkstra. Source cells are
+    // reachable even when blocked, so a field can lead right up to a tree.
+    fn flood(grid: &NavGrid, ground: &GroundMap, sources: &[Vec2]) -> Self {
+        let mut costs = HashMap::new();
+        let mut open = BinaryHeap::new();
+        for &source in sources {
+            let cell = NavGrid::cell(source);
+            costs.insert(cell, 0.0);
+            open.push(Node {estimate: 0.0, cell});
+        }
+        while let Some(Node {estimate: cost, cell}) = open.pop() {
+            if costs.get(&cell).is_some_and(|&known| known < cost) {
+                continue;  // already reached more cheaply
+            };
+            if costs.len() >= MAX_FIELD_CELLS {
+                break;
+            };
+            for (next, step_cost) in neighbours(cell, |cell| grid.cost(ground, cell)) {
+                let next_cost = cost + step_cost;
+                if costs.get(&next).is_some_and(|&known| known <= next_cost) {
+                    continue;
+                };
+                costs.insert(next, next_cost);
+                open.push(Node {estimate: next_cost, cell: next});
+            }
+        }
+        Self {costs}
+    }
+    pub fn cost(&self, position: Vec2) -> Option<f32> {
+        self.costs.get(&NavGrid::cell(position)).copied()
+    }
+    // Heading towards the neighbouring cell that is cheapest, or dearest
+    // when `ascend`ing. `None` off the field or once nothing is better.
+    pub fn direction(&self, position: Vec2, ascend: bool) -> Option<Vec2> {
+        let cell = NavGrid::cell(position);
+        let here = *self.costs.get(&cell)?;
+        let score = |cost: f32| if ascend {-cost} else {cost};
+        let (best, _) = neighbours(cell, |cell| self.costs.get(&cell).map(|_| 1.0))
+            .map(|(next, _)| (next, score(self.costs[&next])))
+            .filter(|&(_, next_score)| next_score < score(here))
+            .min_by(|a, b| a.1.total_cmp(&b.1))?;
+        (NavGrid::cell_center(best) - position).try_normalize()
+    }
+}
+
+// Flow fields for every goal, re-flooded on a timer and whenever the nav
+// grid changes. One field serves every animal heading for the goal, so
+// large herds cost no more than a single animal.
+#[derive(Resource, Default)]
+pub struct FlowFields {
+    sources: HashMap<FlowGoal, Vec<Vec2>>,
+    fields: HashMap<FlowGoal, FlowField>,
+    version: u64,  // of the `NavGrid` the fields were flooded on
+    timer: f32
+} impl FlowFields {
+    pub fn get(&self, goal: FlowGoal) -> Option<&FlowField> {
+        self.fields.get(&goal)
+    }
+    // Where the goal's field flows from, e.g. the herd's gathering point.
+    pub fn sources(&self, goal: FlowGoal) -> &[Vec2] {
+        self.sources.get(&goal).map_or(&[], |sources| sources.as_slice())
+    }
+}
+
+// Gathers each goal's sources and re-floods the fields.
+pub fn update_flow_fields(
+    time: Res<Time>,
+    ground: Option<Res<GroundMap>>,
+    grid: Res<NavGrid>,
+    player_motion: Option<Res<PlayerMotion>>,
+    tree_query: Query<(&Transform, &HealthTracker), With<Tree>>,
+    animal_query: Query<(&Transform, &Species, &DeathTracker), With<Animal>>,
+    mut flow_fields: ResMut<FlowFields>
+) {
+    let Some(ground) = ground else {
+        return;
+    };
+    flow_fields.timer -= time.delta_secs();
+    if flow_fields.timer > 0.0 && flow_fields.version == grid.version() {
+        return;
+    };
+    flow_fields.timer = REFRESH_SECONDS;
+    flow_fields.version = grid.version();
+    let mut sources: HashMap<FlowGoal, Vec<Vec2>> = HashMap::new();
+    sources.insert(FlowGoal::NearestTree, tree_query
+        .iter()
+        .filter(|(_, health_tracker)| health_tracker.is_standing())
+        .map(|(transform, _)| transform.translation.xy())
+        .collect());
+    let mut herds: HashMap<Species, (Vec2, f32)> = HashMap::new();
+    for (transform, &species, _) in animal_query.iter().filter(|(_, _, tracker)| matches!(tracker.lifecycle, DeathLifecycle::Alive)) {
+        let (sum, count) = herds.entry(species).or_default();
+        *sum += transform.translation.xy();
+        *count += 1.0;
+    }
+    for (species, (sum, count)) in herds {
+        sources.insert(FlowGoal::Gather(species), vec![sum / count]);
+    }
+    if let Some(player_motion) = player_motion {
+        sources.insert(FlowGoal::Flee, vec![player_motion.translation]);
+    };
+    flow_fields.fields = sources
+        .iter()
+        .map(|(&goal, goal_sources)| (goal, FlowField::flood(&grid, &ground, goal_sources)))
+        .collect();
+    flow_fields.sources = sources;
+}
@@ -0,0 +1,38 @@
+use bevy::prelude::*;
+
+// Seeded 2D value noise. Every function is pure, so the same seed and
+// position always give the same value.
+
+fn hash(seed: u64, x: i32, y: i32) -> f32 {
+    let mut h = seed ^ (x as u32 as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15) ^ (y as u32 as u64).wrapping_mul(0xc2b2_ae3d_27d4_eb4f);
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    h ^= h >> 33;
+    (h >> 40) as f32 / (1u64 << 24) as f32
+}
+
+// Smoothly interpolated lattice noise, 0.0..1.0.
+pub fn value(seed: u64, position: Vec2) -> f32 {
+    let cell = position.floor();
+    let t = position - cell;
+    let t = t * t * (3.0 - 2.0 * t);
+    let (x, y) = (cell.x as i32, cell.y as i32);
+    let bottom = hash(seed, x, y) + (hash(seed, x + 1, y) - hash(seed, x, y)) * t.x;
+    let top = hash(seed, x, y + 1) + (hash(seed, x + 1, y + 1) - hash(seed, x, y + 1)) * t.x;
+    bottom + (top - bottom) * t.y
+}
+
+// Several octaves of `value` summed for natural looking variation, 0.0..1.0.
+pub fn fbm(seed: u64, position: Vec2, octaves: u32) -> f32 {
+    let mut sum = 0.0;
+    let mut amplitude = 0.5;
+    let mut frequency = 1.0;
+    let mut total = 0.0;
+    for octave in 0..octaves {
+        sum += value(seed.wrapping_add(octave as u64), position * frequency) * amplitude;
+        total += amplitude;
+        amplitude *= 0.5;
+        frequency *= 2.0;
+    }
+    sum / total
+}
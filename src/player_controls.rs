@@ -1,7 +1,11 @@
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
+use serde::Deserialize;
 use crate::camera_module::{POVCameraFollower, CameraPos, VIEW_WIDTH};
 use crate::assets::PropIOU;
+use crate::collision::Collider;
+use crate::audio::SoundEmitter;
+use crate::ground::{GroundFollower, GroundMap};
 
 pub const PLAYER_SPEED: f32 = 4.0;
 pub const PLAYER_DEFAULT_POS: Vec3 = Vec3::new(0.0, 0.0, PLAYER_Z);
@@ -9,6 +13,7 @@ pub const PLAYER_Z: f32 = 0.0;
 
 const SPAWN_PLAYER: bool = true;  // this specifies if the player-test-sphere is spawned
 const PLAYER_RADIUS: f32 = 0.5;
+const PLAYER_MASS: f32 = 4.0;  // heavy enough to shove animals aside
 const SPAWN_MOUSE_FRIEND: bool = true;
 const FRIEND_RADIUS: f32 = 0.125;
 
@@ -31,6 +36,18 @@ pub struct PlayerMotion {
     }
 }
 
+#[derive(Resource, Clone, Deserialize)]
+#[serde(default)]
+pub struct PlayerTuning {
+    pub speed: f32
+} impl Default for PlayerTuning {
+    fn default() -> Self {
+        Self {
+            speed: PLAYER_SPEED
+        }
+    }
+}
+
 #[derive(Resource)]
 pub struct MousePos {
     pub translation2d: Vec2
@@ -39,6 +56,7 @@ pub struct MousePos {
 pub struct PlayerControlsPlugin;
 impl Plugin for PlayerControlsPlugin {
     fn build(&self, app: &mut App) {
+        app.init_resource::<PlayerTuning>();
         app.add_systems(Startup, player_setup);
         app.add_systems(PreUpdate, player_movement);
         app.add_systems(PreUpdate, mouse_translocator);
@@ -49,6 +67,8 @@ impl Plugin for PlayerControlsPlugin {
 fn player_movement(
     keys: Res<ButtonInput<KeyCode>>,
     time: Res<Time>,
+    tuning: Res<PlayerTuning>,
+    ground: Option<Res<GroundMap>>,
     mut player_motion: ResMut<PlayerMotion>,
     mut transform_query: Query<&mut Transform, With<PlayerMarker>>
 ) {
@@ -70,15 +90,33 @@ fn player_movement(
         }
     };
     let player_velocity = if x.abs() + y.abs() > 1 {
-        Vec2::new(x as f32, y as f32).normalize() * PLAYER_SPEED
+        Vec2::new(x as f32, y as f32).normalize() * tuning.speed
     } else {
-        Vec2::new(x as f32, y as f32) * PLAYER_SPEED
+        Vec2::new(x as f32, y as f32) * tuning.speed
+    };
+    let player_velocity = match ground {
+        Some(ground) => blocked_by_ground(&ground, player_transform.translation.xy(), player_velocity, time.delta_secs()),
+        None => player_velocity
     };
     player_transform.translation += (player_velocity * time.delta_secs()).extend(0.0);
     player_motion.velocity = player_velocity;
     player_motion.translation = player_transform.translation.xy();
 }
 
+// Slows the player through shallow water and stops them at deep water or
+// rock, sliding along the edge when only one axis is blocked. A player
+// already standing somewhere unwalkable can always walk out.
+fn blocked_by_ground(ground: &GroundMap, position: Vec2, velocity: Vec2, delta: f32) -> Vec2 {
+    if !ground.is_walkable(position) {
+        return velocity;
+    };
+    let velocity = velocity * ground.speed_factor(position);
+    [velocity, Vec2::new(velocity.x, 0.0), Vec2::new(0.0, velocity.y)]
+        .into_iter()
+        .find(|&velocity| ground.is_walkable(position + velocity * delta))
+        .unwrap_or(Vec2::ZERO)
+}
+
 fn player_setup(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
@@ -93,7 +131,10 @@ fn player_setup(
             // Mesh3d(meshes.add(Sphere::new(PLAYER_RADIUS))),
             // MeshMaterial3d(materials.add(StandardMaterial::from_color(Color::linear_rgb(1.0, 0.0, 0.0)))),
             POVCameraFollower::new(1.0),
-            PropIOU::HoppyCube
+            PropIOU::HoppyCube,
+            SoundEmitter::default(),
+            GroundFollower::default(),
+            Collider::dynamic(PLAYER_RADIUS, PLAYER_MASS)
         ));
     };
     if SPAWN_MOUSE_FRIEND {
@@ -103,7 +144,8 @@ fn player_setup(
             MeshMaterial3d(materials.add(StandardMaterial::from_color(Color::linear_rgb(0.0, 0.0, 1.0)))),
             POVCameraFollower::new(1.0),
             FollowsMouse,
-            PropIOU::HoppyCube
+            PropIOU::HoppyCube,
+            GroundFollower::default()
         ));
     };
 }
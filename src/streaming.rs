@@ -0,0 +1,267 @@
+use std::collections::{HashMap, HashSet};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::animal::{self, Animal, DeathLifecycle, DeathTracker, Species};
+use crate::camera_module::CameraPos;
+use crate::common::RandomSpawnSystem;
+use crate::decoration::{DecorationChunks, DecorationKind, Scatter};
+use crate::ground::{CHUNK_SIZE, GroundMap, Tile};
+use crate::player_controls::PLAYER_DEFAULT_POS;
+use crate::tree::{FellLifecycle, GrowthStage, GrowthTracker, HealthTracker, Tree};
+
+const SEED: u64 = 0x3a7f;
+const LOAD_RADIUS: i32 = 1;  // chunks either side of the camera's chunk kept loaded
+const UNLOAD_RADIUS: i32 = 2;  // further than `LOAD_RADIUS` so chunks don't thrash at the edge
+const TREES_PER_CHUNK: usize = 6;
+const TREE_SPACING: f32 = 3.0;
+const ANIMALS_PER_CHUNK: usize = 2;
+const ANIMAL_SPACING: f32 = 6.0;
+
+#[derive(Serialize, Deserialize)]
+struct SavedTree {
+    position: (f32, f32),
+    stage: GrowthStage,
+    age: f32,
+    health: f32,
+    stump: bool
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedAnimal {
+    position: (f32, f32),
+    species: u32,
+    timeout: f32
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedDecoration {
+    kind: DecorationKind,
+    translation: [f32; 3],
+    rotation: [f32; 4],
+    scale: [f32; 3]
+}
+
+// Trees, animals, decorations and edited ground of a chunk that left range,
+// restored when it returns.
+#[derive(Serialize, Deserialize, Default)]
+struct SavedChunk {
+    trees: Vec<SavedTree>,
+    animals: Vec<SavedAnimal>,
+    decorations: Vec<SavedDecoration>,
+    tiles: Option<Vec<Tile>>  // only when edited, otherwise regenerated from the seed
+}
+
+// Chunks of the world loaded around the camera. A chunk that has never been
+// visited is generated from the seed and its coordinate; one that has is
+// restored from the state it was saved in when it left range.
+#[derive(Resource)]
+pub struct WorldChunks {
+    pub seed: u64,
+    loaded: HashSet<IVec2>,
+    saved: HashMap<IVec2, String>,  // RON encoded `SavedChunk`s
+    saved_animals: HashMap<IVec2, usize>  // living animals in each saved chunk
+} impl Default for WorldChunks {
+    fn default() -> Self {
+        Self {
+            seed: SEED,
+            loaded: HashSet::new(),
+            saved: HashMap::new(),
+            saved_animals: HashMap::new()
+        }
+    }
+} impl WorldChunks {
+    pub fn is_loaded(&self, coord: IVec2) -> bool {
+        self.loaded.contains(&coord)
+    }
+    // Living animals waiting in chunks out of range, which still count
+    // towards the population though none of their entities exist.
+    pub fn saved_living_animals(&self) -> usize {
+        self.saved_animals.values().sum()
+    }
+    fn chunk_seed(&self, coord: IVec2, salt: u64) -> u64 {
+        (self.seed ^ ((coord.x as u32 as u64) << 32) ^ coord.y as u32 as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15).wrapping_add(salt)
+    }
+}
+
+pub struct WorldStreamingPlugin;
+impl Plugin for WorldStreamingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WorldChunks>();
+        app.add_systems(Startup, stream_chunks);
+        app.add_systems(PreUpdate, stream_chunks);
+    }
+}
+
+fn stream_chunks(world: &mut World) {
+    let center = world.get_resource::<CameraPos>().map_or(PLAYER_DEFAULT_POS.xy(), |camera| camera.vec2);
+    let center = GroundMap::chunk_coord(center);
+    let unsettled = unsettled_chunks(world);
+    let out_of_range: Vec<IVec2> = world.resource::<WorldChunks>().loaded
+        .iter()
+        .copied()
+        .filter(|coord| (*coord - center).abs().max_element() > UNLOAD_RADIUS && !unsettled.contains(coord))
+        .collect();
+    for coord in out_of_range {
+        unload_chunk(world, coord);
+    }
+    for x in -LOAD_RADIUS..=LOAD_RADIUS {
+        for y in -LOAD_RADIUS..=LOAD_RADIUS {
+            let coord = center + IVec2::new(x, y);
+            if !world.resource::<WorldChunks>().is_loaded(coord) {
+                load_chunk(world, coord);
+            };
+        }
+    }
+}
+
+// Chunks holding an animal that is ramming or drowning. They stay loaded
+// until it has settled, so it can't vanish halfway through.
+fn unsettled_chunks(world: &mut World) -> HashSet<IVec2> {
+    world.query_filtered::<(&Transform, &DeathTracker), With<Animal>>()
+        .iter(world)
+        .filter(|(_, death_tracker)| matches!(death_tracker.lifecycle, DeathLifecycle::Ramming(_) | DeathLifecycle::Drowning(_)))
+        .map(|(transform, _)| GroundMap::chunk_coord(transform.translation.xy()))
+        .collect()
+}
+
+fn load_chunk(world: &mut World, coord: IVec2) {
+    let mut chunks = world.resource_mut::<WorldChunks>();
+    chunks.saved_animals.remove(&coord);
+    let saved = chunks.saved.remove(&coord);
+    match saved.map(|saved| ron::from_str::<SavedChunk>(&saved)) {
+        Some(Ok(saved)) => restore_chunk(world, coord, saved),
+        Some(Err(error)) => {
+            warn!("could not restore chunk {coord}, regenerating it: {error}");
+            generate_chunk(world, coord);
+        },
+        None => generate_chunk(world, coord)
+    }
+    world.resource_mut::<WorldChunks>().loaded.insert(coord);
+}
+
+fn generate_chunk(world: &mut World, coord: IVec2) {
+    world.resource_mut::<GroundMap>().generate_chunk(coord);
+    let origin = coord.as_vec2() * CHUNK_SIZE;
+    let chunks = world.resource::<WorldChunks>();
+    let (tree_seed, animal_seed) = (chunks.chunk_seed(coord, 1), chunks.chunk_seed(coord, 2));
+    RandomSpawnSystem::<Tree>::on_chunk(world, origin, CHUNK_SIZE, tree_seed, TREES_PER_CHUNK, TREE_SPACING);
+    RandomSpawnSystem::<Animal>::on_chunk(world, origin, CHUNK_SIZE, animal_seed, ANIMALS_PER_CHUNK, ANIMAL_SPACING);
+    let trees: Vec<Vec2> = world.query_filtered::<&Transform, With<Tree>>()
+        .iter(world)
+        .map(|transform| transform.translation.xy())
+        .collect();
+    world.resource_scope(|world, mut decorations: Mut<DecorationChunks>| {
+        let scatter = world.resource::<Scatter>();
+        let ground = world.get_resource::<GroundMap>();
+        decorations.scatter_chunk(coord, scatter, ground, &trees);
+    });
+}
+
+fn restore_chunk(world: &mut World, coord: IVec2, saved: SavedChunk) {
+    let mut ground = world.resource_mut::<GroundMap>();
+    match saved.tiles {
+        Some(tiles) => ground.restore_chunk(coord, tiles),
+        None => ground.generate_chunk(coord)
+    }
+    for tree in saved.trees {
+        let mut health_tracker = HealthTracker {
+            health: tree.health,
+            ..default()
+        };
+        if tree.stump {
+            health_tracker.lifecycle = FellLifecycle::Stump;
+        };
+        let growth_tracker = GrowthTracker {
+            stage: tree.stage,
+            age: tree.age
+        };
+        let position = Vec2::from(tree.position).extend(0.0);
+        world.spawn(Tree::bundle(position, tree.stage)).insert((health_tracker, growth_tracker));
+    }
+    let tuning = world.resource::<animal::Tuning>().clone();
+    for animal in saved.animals {
+        let death_tracker = DeathTracker {
+            lifecycle: DeathLifecycle::Alive,
+            timeout: animal.timeout
+        };
+        world.spawn(Animal::bundle(animal.position.into(), &tuning)).insert((Species(animal.species), death_tracker));
+    }
+    let decorations = saved.decorations
+        .into_iter()
+        .map(|decoration| {
+            let transform = Transform {
+                translation: Vec3::from_array(decoration.translation),
+                rotation: Quat::from_array(decoration.rotation),
+                scale: Vec3::from_array(decoration.scale)
+            };
+            (decoration.kind, transform)
+        })
+        .collect();
+    world.resource_mut::<DecorationChunks>().restore_chunk(coord, decorations);
+}
+
+// Saves the trees, living animals, decorations and edited ground of the
+// chunk, then despawns everything belonging to it. Animals that are still
+// dying keep their chunk loaded, so the only ones dropped are corpses.
+fn unload_chunk(world: &mut World, coord: IVec2) {
+    let mut saved = SavedChunk::default();
+    let mut despawn = Vec::new();
+    let mut tree_query = world.query_filtered::<(Entity, &Transform, &HealthTracker, &GrowthTracker), With<Tree>>();
+    for (entity, transform, health_tracker, growth_tracker) in tree_query.iter(world) {
+        let position = transform.translation.xy();
+        if GroundMap::chunk_coord(position) != coord {
+            continue;
+        };
+        saved.trees.push(SavedTree {
+            position: position.into(),
+            stage: growth_tracker.stage,
+            age: growth_tracker.age,
+            health: health_tracker.health,
+            stump: !health_tracker.is_standing()  // a falling tree has landed by the time anyone is back
+        });
+        despawn.push(entity);
+    }
+    let mut animal_query = world.query_filtered::<(Entity, &Transform, &Species, &DeathTracker), With<Animal>>();
+    for (entity, transform, species, death_tracker) in animal_query.iter(world) {
+        let position = transform.translation.xy();
+        if GroundMap::chunk_coord(position) != coord {
+            continue;
+        };
+        if matches!(death_tracker.lifecycle, DeathLifecycle::Alive) {
+            saved.animals.push(SavedAnimal {
+                position: position.into(),
+                species: species.0,
+                timeout: death_tracker.timeout
+            });
+        };
+        despawn.push(entity);
+    }
+    if let Some(chunk) = world.resource::<DecorationChunks>().chunks.get(&coord) {
+        saved.decorations = chunk.placements
+            .iter()
+            .map(|(kind, transform)| SavedDecoration {
+                kind: *kind,
+                translation: transform.translation.to_array(),
+                rotation: transform.rotation.to_array(),
+                scale: transform.scale.to_array()
+            })
+            .collect();
+    };
+    saved.tiles = world.resource::<GroundMap>().edited_tiles(coord);
+    despawn.extend(world.resource_mut::<GroundMap>().unload_chunk(coord));
+    despawn.extend(world.resource_mut::<DecorationChunks>().unload_chunk(coord));
+    for entity in despawn {
+        world.despawn(entity);
+    }
+    let mut chunks = world.resource_mut::<WorldChunks>();
+    chunks.loaded.remove(&coord);
+    let living = saved.animals.len();
+    match ron::to_string(&saved) {
+        Ok(saved) => {
+            chunks.saved.insert(coord, saved);
+            chunks.saved_animals.insert(coord, living);
+        },
+        Err(error) => warn!("could not save chunk {coord}, it will be regenerated: {error}")
+    }
+}
+
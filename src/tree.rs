@@ -1,22 +1,461 @@
 use super::*;
 
+pub const HEALTH: f32 = 3.0;
+pub const IMPACT_DAMAGE: f32 = 1.0;
+pub const SHAKE_SECONDS: f32 = 0.6;
+pub const SHAKE_ANGLE: f32 = 0.25;
+pub const SHAKE_FREQUENCY: f32 = 6.0;
+pub const FALL_SECONDS: f32 = 1.2;
+pub const SWAY_ANGLE: f32 = 0.08;
+pub const SWAY_SPEED: f32 = 1.5;
+pub const RADIUS: f32 = 0.3;
+pub const SAPLING_SECONDS: f32 = 60.0;
+pub const YOUNG_SECONDS: f32 = 120.0;
+pub const STUMP_SECONDS: f32 = 90.0;
+pub const GROWTH_RATE: f32 = 0.5;
+pub const REGROW_INTERVAL_SECONDS: f32 = 5.0;
+pub const SEED_MIN_DISTANCE: f32 = 1.5;
+pub const SEED_MAX_DISTANCE: f32 = 4.0;
+pub const DENSITY_RADIUS: f32 = 3.0;
+pub const MAX_NEIGHBOURS: usize = 3;
+pub const MAX_TREES: usize = 400;
+
 pub struct Plugin;
 
 impl ::bevy::prelude::Plugin for Plugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, |world: &mut World| common::RandomSpawnSystem::<Tree>::on_startup(world));
+        app.add_event::<HitEvent>();
+        app.add_event::<FelledEvent>();
+        app.add_event::<StumpEvent>();
+        app.add_event::<GrowEvent>();
+        app.add_systems(Update, ImpactSystem::on_update.after(animal::DeathSystem::on_update));
+        app.add_systems(Update, FellSystem::on_update.after(ImpactSystem::on_update));
+        app.add_systems(Update, PoseSystem::on_update.after(FellSystem::on_update));
+        app.add_systems(Update, GrowthSystem::on_update.after(FellSystem::on_update));
+        app.add_systems(Update, RegrowSystem::on_update.after(GrowthSystem::on_update));
     }
 }
 
 #[derive(Component)]
 #[require(Transform)]
+#[require(Visibility)]
 pub struct Tree;
 
-impl common::RandomSpawnEntityConstructor for Tree {
-    type Bundle = (Self, Transform);
-
-    fn new(_: &World, position: Vec3) -> Self::Bundle {(
+impl Tree {
+    pub fn bundle(position: Vec3, stage: GrowthStage) -> (Self, Transform, HealthTracker, GrowthTracker, assets::PropIOU, audio::SoundEmitter, ground::GroundFollower, collision::Collider) {(
         Self,
-        position.into()
+        Transform::from_translation(position).with_scale(Vec3::splat(stage.scale())),
+        HealthTracker::default(),
+        GrowthTracker::new(stage),
+        Self::random_prop(),
+        audio::SoundEmitter::default(),
+        ground::GroundFollower::default(),
+        collision::Collider::fixed(RADIUS)  // scaled with the tree as it grows
     )}
-}
\ No newline at end of file
+
+    pub fn random_prop() -> assets::PropIOU {
+        if ::fastrand::bool() {
+            assets::PropIOU::PineTree
+        } else {
+            assets::PropIOU::OakTree
+        }
+    }
+}
+
+impl common::RandomSpawnEntityConstructor for Tree {
+    type Bundle = (Self, Transform, HealthTracker, GrowthTracker, assets::PropIOU, audio::SoundEmitter, ground::GroundFollower, collision::Collider);
+
+    fn new(_: &World, position: Vec3) -> Self::Bundle {
+        Self::bundle(position, GrowthStage::Mature)
+    }
+}
+
+
+// === Health ===
+// Trees lose health when rammed, and once it runs out they fall
+// over and are left as a stump.
+
+pub struct FallingLifecycle {
+    pub axis: Vec3,
+    pub progress: f32
+}
+
+#[derive(Default)]
+pub enum FellLifecycle {
+    #[default]
+    Standing,
+    Falling(FallingLifecycle),
+    Stump
+}
+
+#[derive(Component)]
+pub struct HealthTracker {
+    pub lifecycle: FellLifecycle,
+    pub health: f32,
+    pub shake: f32,
+    pub shake_axis: Vec3
+}
+
+impl HealthTracker {
+    pub fn is_standing(&self) -> bool {
+        matches!(self.lifecycle, FellLifecycle::Standing)
+    }
+}
+
+impl Default for HealthTracker {
+    fn default() -> Self {
+        Self {
+            lifecycle: FellLifecycle::default(),
+            health: HEALTH,
+            shake: 0.0,
+            shake_axis: Vec3::X
+        }
+    }
+}
+
+// Axis a tree tips about to lean towards `direction`.
+fn tilt_axis(direction: Vec2) -> Vec3 {
+    Vec3::new(-direction.y, direction.x, 0.0)
+}
+
+
+// === Impact System ===
+// Applies ramming damage to the standing tree an animal hit.
+
+// Written when a tree takes damage.
+#[derive(Event)]
+#[derive(Clone)]
+#[derive(Copy)]
+pub struct HitEvent {
+    pub entity: Entity,
+    pub position: Vec2,
+    pub health: f32
+}
+
+// Written when a tree runs out of health and starts to fall.
+#[derive(Event)]
+#[derive(Clone)]
+#[derive(Copy)]
+pub struct FelledEvent {
+    pub entity: Entity,
+    pub position: Vec2,
+    pub direction: Vec2
+}
+
+pub struct ImpactSystem;
+
+impl ImpactSystem {
+    pub fn on_update(mut impact_reader: EventReader<animal::ImpactEvent>, mut trees: Query<(&Transform, &mut HealthTracker), With<Tree>>, mut hit_writer: EventWriter<HitEvent>, mut felled_writer: EventWriter<FelledEvent>) {
+        for impact in impact_reader.read() {
+            let entity: Entity = impact.tree;
+            let Ok((transform, mut tracker)) = trees.get_mut(entity) else {
+                continue
+            };
+            if !tracker.is_standing() {
+                continue  // felled by an earlier impact this frame
+            }
+            let position: Vec2 = transform.translation.truncate();
+            let direction: Vec2 = (position - impact.position).normalize_or(Vec2::X);
+            tracker.health -= IMPACT_DAMAGE;
+            tracker.shake = SHAKE_SECONDS;
+            tracker.shake_axis = tilt_axis(direction);
+            let event: HitEvent = HitEvent {
+                entity,
+                position,
+                health: tracker.health.max(0.0)
+            };
+            hit_writer.write(event);
+            if tracker.health > 0.0 {
+                continue
+            }
+            let lifecycle: FallingLifecycle = FallingLifecycle {
+                axis: tilt_axis(direction),
+                progress: 0.0
+            };
+            tracker.lifecycle = FellLifecycle::Falling(lifecycle);
+            let event: FelledEvent = FelledEvent {
+                entity,
+                position,
+                direction
+            };
+            felled_writer.write(event);
+        }
+    }
+}
+
+
+// === Fell System ===
+// Topples felled trees and leaves a stump behind.
+
+// Written when a felled tree has finished falling.
+#[derive(Event)]
+#[derive(Clone)]
+#[derive(Copy)]
+pub struct StumpEvent {
+    pub entity: Entity,
+    pub position: Vec2
+}
+
+pub struct FellSystem;
+
+impl FellSystem {
+    pub fn on_update(mut trees: Query<(Entity, &Transform, &mut HealthTracker, &mut GrowthTracker), With<Tree>>, time: Res<Time>, mut stump_writer: EventWriter<StumpEvent>) {
+        let delta: f32 = time.delta_secs();
+        for (entity, transform, mut tracker, mut growth_tracker) in trees.iter_mut() {
+            let FellLifecycle::Falling(lifecycle) = &mut tracker.lifecycle else {
+                continue
+            };
+            lifecycle.progress += delta / FALL_SECONDS;
+            if lifecycle.progress < 1.0 {
+                continue
+            }
+            tracker.lifecycle = FellLifecycle::Stump;
+            growth_tracker.age = 0.0;  // now counts how long the stump has stood
+            let event: StumpEvent = StumpEvent {
+                entity,
+                position: transform.translation.truncate()
+            };
+            stump_writer.write(event);
+        }
+    }
+}
+
+
+// === Pose System ===
+// Orients each tree's crown (see `assets::TreeCrown`): swaying in the
+// wind, shaking after a hit, toppling when felled and hidden once only
+// the stump is left.
+
+pub struct PoseSystem;
+
+type CrownQuery<'w, 's> = Query<'w, 's, (&'static ChildOf, &'static mut Transform, &'static mut Visibility), (With<assets::TreeCrown>, Without<Tree>)>;
+
+impl PoseSystem {
+    pub fn on_update(mut trees: Query<(&Transform, &mut HealthTracker), With<Tree>>, mut crowns: CrownQuery, time: Res<Time>, wind: Option<Res<environment::Wind>>) {
+        let delta: f32 = time.delta_secs();
+        let elapsed: f32 = time.elapsed_secs();
+        let wind: Vec2 = wind.map_or(Vec2::ZERO, |wind| wind.vector);
+        let sway_axis: Vec3 = tilt_axis(wind.normalize_or_zero());
+        for (child_of, mut crown, mut visibility) in crowns.iter_mut() {
+            let Ok((transform, mut tracker)) = trees.get_mut(child_of.parent()) else {
+                continue
+            };
+            tracker.shake = (tracker.shake - delta).max(0.0);
+            match &tracker.lifecycle {
+                FellLifecycle::Standing => {
+                    let phase: f32 = transform.translation.x * 0.37 + transform.translation.y * 0.73;
+                    let sway: f32 = wind.length() * SWAY_ANGLE * (0.75 + 0.25 * (elapsed * SWAY_SPEED + phase).sin());
+                    let fade: f32 = tracker.shake / SHAKE_SECONDS;
+                    let shake: f32 = SHAKE_ANGLE * fade * fade * (elapsed * SHAKE_FREQUENCY * ::std::f32::consts::TAU).sin();
+                    crown.rotation = Quat::from_axis_angle(tracker.shake_axis, shake) * Quat::from_axis_angle(sway_axis, sway);
+                    *visibility = Visibility::Inherited;
+                },
+                FellLifecycle::Falling(lifecycle) => {
+                    let t: f32 = lifecycle.progress.clamp(0.0, 1.0);
+                    let angle: f32 = ::std::f32::consts::FRAC_PI_2 * t * t;
+                    crown.rotation = Quat::from_axis_angle(lifecycle.axis, angle);
+                },
+                FellLifecycle::Stump => {
+                    *visibility = Visibility::Hidden;
+                }
+            }
+        }
+    }
+}
+
+
+// === Growth System ===
+// Trees grow from saplings into mature trees, their prop and collision
+// radius scaling with them.
+
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(PartialEq)]
+#[derive(Eq)]
+#[derive(Debug)]
+#[derive(::serde::Serialize)]
+#[derive(::serde::Deserialize)]
+pub enum GrowthStage {
+    Sapling,
+    Young,
+    Mature
+}
+
+impl GrowthStage {
+    pub fn scale(&self) -> f32 {
+        match self {
+            Self::Sapling => 0.35,
+            Self::Young => 0.65,
+            Self::Mature => 1.0
+        }
+    }
+
+    pub fn radius(&self) -> f32 {
+        RADIUS * self.scale()
+    }
+
+    // Seconds spent in this stage before growing into the next.
+    fn duration(&self) -> Option<f32> {
+        match self {
+            Self::Sapling => Some(SAPLING_SECONDS),
+            Self::Young => Some(YOUNG_SECONDS),
+            Self::Mature => None
+        }
+    }
+
+    fn next(&self) -> Self {
+        match self {
+            Self::Sapling => Self::Young,
+            Self::Young | Self::Mature => Self::Mature
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct GrowthTracker {
+    pub stage: GrowthStage,
+    pub age: f32
+}
+
+impl GrowthTracker {
+    pub fn new(stage: GrowthStage) -> Self {
+        Self {
+            stage,
+            age: 0.0
+        }
+    }
+
+    pub fn radius(&self) -> f32 {
+        self.stage.radius()
+    }
+}
+
+// Written when a tree grows into its next stage.
+#[derive(Event)]
+#[derive(Clone)]
+#[derive(Copy)]
+pub struct GrowEvent {
+    pub entity: Entity,
+    pub stage: GrowthStage
+}
+
+pub struct GrowthSystem;
+
+impl GrowthSystem {
+    pub fn on_update(mut trees: Query<(Entity, &mut Transform, &mut GrowthTracker, &HealthTracker), With<Tree>>, time: Res<Time>, mut grow_writer: EventWriter<GrowEvent>) {
+        let delta: f32 = time.delta_secs();
+        for (entity, mut transform, mut tracker, health_tracker) in trees.iter_mut() {
+            if !health_tracker.is_standing() {
+                continue
+            }
+            tracker.age += delta;
+            if let Some(duration) = tracker.stage.duration() && tracker.age >= duration {
+                tracker.stage = tracker.stage.next();
+                tracker.age = 0.0;
+                let event: GrowEvent = GrowEvent {
+                    entity,
+                    stage: tracker.stage
+                };
+                grow_writer.write(event);
+            }
+            let target: f32 = tracker.stage.scale();
+            let scale: f32 = transform.scale.x + (target - transform.scale.x) * (GROWTH_RATE * delta).min(1.0);
+            transform.scale = Vec3::splat(scale);
+        }
+    }
+}
+
+
+// === Regrow System ===
+// Stumps resprout after a while, and mature trees scatter seeds onto
+// open ground nearby as long as it isn't already crowded.
+
+pub struct RegrowSystem {
+    x: f32,
+    y: f32,
+    tree_positions: Vec<(f32, f32)>
+}
+
+impl RegrowSystem {
+    pub fn on_update(mut commands: Commands, mut trees: Query<(&mut Transform, &mut HealthTracker, &mut GrowthTracker), With<Tree>>, ground: Option<Res<ground::GroundMap>>, time: Res<Time>, mut timer: Local<f32>) {
+        let delta: f32 = time.delta_secs();
+        for (mut transform, mut health_tracker, mut growth_tracker) in trees.iter_mut() {
+            if !matches!(health_tracker.lifecycle, FellLifecycle::Stump) {
+                continue
+            }
+            growth_tracker.age += delta;
+            if growth_tracker.age < STUMP_SECONDS {
+                continue
+            }
+            *health_tracker = HealthTracker::default();
+            *growth_tracker = GrowthTracker::new(GrowthStage::Sapling);
+            transform.scale = Vec3::splat(GrowthStage::Sapling.scale());
+        }
+        *timer -= delta;
+        if *timer > 0.0 {
+            return
+        }
+        *timer = REGROW_INTERVAL_SECONDS;
+        let tree_positions: Vec<(f32, f32)> = trees
+            .iter()
+            .map(|(transform, _, _)| {
+                let position: Vec2 = transform.translation.truncate();
+                (position.x, position.y)
+            })
+            .collect();
+        if tree_positions.len() >= MAX_TREES {
+            return
+        }
+        let parents: Vec<Vec2> = trees
+            .iter()
+            .filter(|(_, health_tracker, growth_tracker)| {
+                health_tracker.is_standing() && growth_tracker.stage == GrowthStage::Mature
+            })
+            .map(|(transform, _, _)| transform.translation.truncate())
+            .collect();
+        if parents.is_empty() {
+            return
+        }
+        let parent: Vec2 = parents[::fastrand::usize(..parents.len())];
+        let model: Self = Self {
+            x: parent.x,
+            y: parent.y,
+            tree_positions
+        };
+        let Some(position) = model.simulate() else {
+            return
+        };
+        if ground.is_some_and(|ground| !ground.is_fertile(position)) {
+            return  // water, rock or ground that isn't loaded
+        }
+        commands.spawn(Tree::bundle(position.extend(0.0), GrowthStage::Sapling));
+    }
+}
+
+impl Model for RegrowSystem {
+    type Output = Option<Vec2>;
+
+    // Picks a spot near the parent tree for a seed to land, rejecting it
+    // if it is too close to another tree or the area is already dense.
+    fn simulate(self) -> Self::Output {
+        let parent: Vec2 = (self.x, self.y).into();
+        let angle: f32 = ::fastrand::f32() * ::std::f32::consts::TAU;
+        let distance: f32 = SEED_MIN_DISTANCE + ::fastrand::f32() * (SEED_MAX_DISTANCE - SEED_MIN_DISTANCE);
+        let position: Vec2 = parent + Vec2::from_angle(angle) * distance;
+        let tree_positions: Vec<Vec2> = self.tree_positions
+            .iter()
+            .map(|&(x, y)| (x, y).into())
+            .collect();
+        if tree_positions.iter().any(|other| other.distance(position) < SEED_MIN_DISTANCE) {
+            return None
+        }
+        let neighbours: usize = tree_positions
+            .iter()
+            .filter(|other| other.distance(position) < DENSITY_RADIUS)
+            .count();
+        if neighbours >= MAX_NEIGHBOURS {
+            return None
+        }
+        Some(position)
+    }
+}
@@ -0,0 +1,117 @@
+use std::time::SystemTime;
+use bevy::prelude::*;
+use serde::Deserialize;
+use crate::{animal, assets, camera_module, environment, player_controls};
+
+const TUNING_PATH: &str = "assets/tuning.ron";
+const POLL_SECONDS: f32 = 0.5;  // how often the tuning file's modified time is checked
+
+// Every section is optional, missing sections and fields keep their defaults.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct TuningFile {
+    player: player_controls::PlayerTuning,
+    camera: camera_module::CameraTuning,
+    environment: environment::EnvironmentTuning,
+    hop: assets::HopTuning,
+    animal: animal::Tuning
+}
+
+#[derive(Debug, thiserror::Error)]
+enum TuningError {
+    #[error("could not read `{TUNING_PATH}`: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse `{TUNING_PATH}`: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("invalid tuning in `{TUNING_PATH}`: `{min}` is greater than `{max}`")]
+    Range {min: &'static str, max: &'static str}
+}
+
+impl TuningFile {
+    // Rejects ranges whose bounds are swapped, which would roll negative
+    // cooldowns and timeouts.
+    fn validate(&self) -> Result<(), TuningError> {
+        let ranges = [
+            ("animal.min_motion_sleep_seconds", self.animal.min_motion_sleep_seconds, "animal.max_motion_sleep_seconds", self.animal.max_motion_sleep_seconds),
+            ("animal.min_death_timeout_seconds", self.animal.min_death_timeout_seconds, "animal.max_death_timeout_seconds", self.animal.max_death_timeout_seconds)
+        ];
+        for (min, low, max, high) in ranges {
+            if low > high {
+                return Err(TuningError::Range {min, max});
+            };
+        }
+        Ok(())
+    }
+}
+
+#[derive(Resource)]
+struct TuningWatcher {
+    timer: Timer,
+    last_modified: Option<SystemTime>,
+    missing_reported: bool
+}
+
+// Watches `TUNING_PATH` and re-inserts the typed tuning resources whenever
+// the file is saved. Parse and validation errors are logged and the previous values kept.
+pub struct TuningPlugin;
+impl Plugin for TuningPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(TuningWatcher {
+            timer: Timer::from_seconds(POLL_SECONDS, TimerMode::Repeating),
+            last_modified: None,
+            missing_reported: false
+        });
+        app.add_systems(PreStartup, watch_tuning_file);
+        app.add_systems(PreUpdate, tick_tuning_watcher);
+    }
+}
+
+fn tick_tuning_watcher(
+    time: Res<Time>,
+    mut watcher: ResMut<TuningWatcher>,
+    commands: Commands
+) {
+    if !watcher.timer.tick(time.delta()).just_finished() {
+        return;
+    };
+    watch_tuning_file(watcher, commands);
+}
+
+fn watch_tuning_file(
+    mut watcher: ResMut<TuningWatcher>,
+    mut commands: Commands
+) {
+    let modified = match std::fs::metadata(TUNING_PATH).and_then(|m| m.modified()) {
+        Ok(m) => m,
+        Err(e) => {
+            if !watcher.missing_reported {
+                warn!("{}, using default tuning", TuningError::from(e));
+                watcher.missing_reported = true;
+            };
+            return;
+        }
+    };
+    watcher.missing_reported = false;
+    if watcher.last_modified == Some(modified) {
+        return;
+    };
+    watcher.last_modified = Some(modified);
+    match load_tuning_file() {
+        Ok(file) => {
+            commands.insert_resource(file.player);
+            commands.insert_resource(file.camera);
+            commands.insert_resource(file.environment);
+            commands.insert_resource(file.hop);
+            commands.insert_resource(file.animal);
+            info!("applied tuning from `{TUNING_PATH}`");
+        },
+        Err(e) => error!("{e}, keeping previous tuning")
+    }
+}
+
+fn load_tuning_file() -> Result<TuningFile, TuningError> {
+    let text = std::fs::read_to_string(TUNING_PATH)?;
+    let file: TuningFile = ron::from_str(&text)?;
+    file.validate()?;
+    Ok(file)
+}